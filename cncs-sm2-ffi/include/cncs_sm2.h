@@ -0,0 +1,242 @@
+#ifndef CNCS_SM2_H
+#define CNCS_SM2_H
+
+/* Generated by cbindgen from src/, see README.md. Do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define CNCS_SM2_MODE_C1C3C2 0
+
+#define CNCS_SM2_MODE_C1C2C3 1
+
+#define CNCS_SM2_PRIVATE_KEY_LEN 32
+
+// x || y, without the 04 prefix.
+#define CNCS_SM2_PUBLIC_KEY_LEN 64
+
+// r || s.
+#define CNCS_SM2_SIGNATURE_LEN 64
+
+#define CNCS_SM2_OK 0
+
+// A required pointer argument is NULL.
+#define CNCS_SM2_ERR_NULL_POINTER -1
+
+// An unknown mode, or a string argument that is not UTF-8.
+#define CNCS_SM2_ERR_INVALID_ARGUMENT -2
+
+// Malformed hex, DER or PEM, or an input of the wrong length.
+#define CNCS_SM2_ERR_PARSE -3
+
+#define CNCS_SM2_ERR_INVALID_PRIVATE_KEY -4
+
+#define CNCS_SM2_ERR_INVALID_PUBLIC_KEY -5
+
+#define CNCS_SM2_ERR_INVALID_SIGNATURE -6
+
+// Longer than 8191 bytes.
+#define CNCS_SM2_ERR_INVALID_USER_ID -7
+
+// Malformed or tampered ciphertext.
+#define CNCS_SM2_ERR_DECRYPTION -8
+
+// The output buffer is NULL or too small, `*out_len` holds the length needed.
+#define CNCS_SM2_ERR_BUFFER_TOO_SMALL -9
+
+// A well-formed signature that does not match.
+#define CNCS_SM2_ERR_VERIFICATION_FAILED -10
+
+// A bug, caught before it unwound into the caller.
+#define CNCS_SM2_ERR_PANIC -99
+
+// Opaque, free with `cncs_sm2_private_key_free`.
+typedef struct CncsSm2PrivateKey CncsSm2PrivateKey;
+
+// Opaque, free with `cncs_sm2_public_key_free`.
+typedef struct CncsSm2PublicKey CncsSm2PublicKey;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Length of the ciphertext of a `msg_len`-byte message, 97 bytes more.
+size_t cncs_sm2_ciphertext_len(size_t msg_len);
+
+// Writes the 04-prefixed ciphertext, of `cncs_sm2_ciphertext_len(msg_len)` bytes, in the
+// `CNCS_SM2_MODE_*` byte order `mode`.
+//
+// # Safety
+//
+// `public_key` must come from this library, `msg` be valid for `msg_len` bytes of reads,
+// `out` NULL or valid for `*out_len` bytes of writes, `out_len` valid for reads and writes.
+int32_t cncs_sm2_encrypt(const struct CncsSm2PublicKey *public_key, const uint8_t *msg, size_t msg_len, int32_t mode, uint8_t *out, size_t *out_len);
+
+// Writes the plaintext, 97 bytes shorter than the ciphertext.
+//
+// # Safety
+//
+// `private_key` must come from this library, `ciphertext` be valid for `ciphertext_len`
+// bytes of reads, `out` NULL or valid for `*out_len` bytes of writes, `out_len` valid for
+// reads and writes.
+int32_t cncs_sm2_decrypt(const struct CncsSm2PrivateKey *private_key, const uint8_t *ciphertext, size_t ciphertext_len, int32_t mode, uint8_t *out, size_t *out_len);
+
+// Copies the message of the last error on this thread, NUL-terminated, into `out`.
+//
+// Returns `CNCS_SM2_ERR_BUFFER_TOO_SMALL` with the length needed, including the NUL, in
+// `*out_len` when `out` is NULL or shorter. An empty message means no error so far.
+//
+// # Safety
+//
+// `out` must be NULL or valid for `*out_len` bytes of writes, `out_len` valid for reads and
+// writes.
+int32_t cncs_sm2_last_error_message(char *out, size_t *out_len);
+
+// # Safety
+//
+// `out` must be valid for writes.
+int32_t cncs_sm2_private_key_generate(struct CncsSm2PrivateKey **out);
+
+// 64 hex characters, NUL-terminated.
+//
+// # Safety
+//
+// `hex` must be a NUL-terminated string, `out` valid for writes.
+int32_t cncs_sm2_private_key_from_hex(const char *hex, struct CncsSm2PrivateKey **out);
+
+// 32 bytes, big-endian.
+//
+// # Safety
+//
+// `bytes` must be valid for `len` bytes of reads, `out` valid for writes.
+int32_t cncs_sm2_private_key_from_bytes(const uint8_t *bytes, size_t len, struct CncsSm2PrivateKey **out);
+
+// PKCS#8, or SEC1 ECPrivateKey.
+//
+// # Safety
+//
+// `der` must be valid for `len` bytes of reads, `out` valid for writes.
+int32_t cncs_sm2_private_key_from_der(const uint8_t *der, size_t len, struct CncsSm2PrivateKey **out);
+
+// `PRIVATE KEY`, `EC PRIVATE KEY` or `SM2 PRIVATE KEY`, NUL-terminated.
+//
+// # Safety
+//
+// `pem` must be a NUL-terminated string, `out` valid for writes.
+int32_t cncs_sm2_private_key_from_pem(const char *pem, struct CncsSm2PrivateKey **out);
+
+// Writes the 32 bytes of d.
+//
+// # Safety
+//
+// `private_key` must come from this library, `out` be NULL or valid for `*out_len` bytes of
+// writes, `out_len` valid for reads and writes.
+int32_t cncs_sm2_private_key_to_bytes(const struct CncsSm2PrivateKey *private_key, uint8_t *out, size_t *out_len);
+
+// Writes PKCS#8 DER.
+//
+// # Safety
+//
+// As `cncs_sm2_private_key_to_bytes`.
+int32_t cncs_sm2_private_key_to_der(const struct CncsSm2PrivateKey *private_key, uint8_t *out, size_t *out_len);
+
+// # Safety
+//
+// `private_key` must come from this library, `out` be valid for writes.
+int32_t cncs_sm2_private_key_public_key(const struct CncsSm2PrivateKey *private_key, struct CncsSm2PublicKey **out);
+
+// NULL is ignored.
+//
+// # Safety
+//
+// `private_key` must come from this library and not be used afterwards.
+void cncs_sm2_private_key_free(struct CncsSm2PrivateKey *private_key);
+
+// x || y as 128 hex characters, with or without the 04 prefix, NUL-terminated.
+//
+// # Safety
+//
+// `hex` must be a NUL-terminated string, `out` valid for writes.
+int32_t cncs_sm2_public_key_from_hex(const char *hex, struct CncsSm2PublicKey **out);
+
+// x || y, with or without the 04 prefix.
+//
+// # Safety
+//
+// `bytes` must be valid for `len` bytes of reads, `out` valid for writes.
+int32_t cncs_sm2_public_key_from_bytes(const uint8_t *bytes, size_t len, struct CncsSm2PublicKey **out);
+
+// SubjectPublicKeyInfo.
+//
+// # Safety
+//
+// `der` must be valid for `len` bytes of reads, `out` valid for writes.
+int32_t cncs_sm2_public_key_from_der(const uint8_t *der, size_t len, struct CncsSm2PublicKey **out);
+
+// `PUBLIC KEY`, NUL-terminated.
+//
+// # Safety
+//
+// `pem` must be a NUL-terminated string, `out` valid for writes.
+int32_t cncs_sm2_public_key_from_pem(const char *pem, struct CncsSm2PublicKey **out);
+
+// Writes the 64 bytes of x || y.
+//
+// # Safety
+//
+// `public_key` must come from this library, `out` be NULL or valid for `*out_len` bytes of
+// writes, `out_len` valid for reads and writes.
+int32_t cncs_sm2_public_key_to_bytes(const struct CncsSm2PublicKey *public_key, uint8_t *out, size_t *out_len);
+
+// Writes SubjectPublicKeyInfo DER.
+//
+// # Safety
+//
+// As `cncs_sm2_public_key_to_bytes`.
+int32_t cncs_sm2_public_key_to_der(const struct CncsSm2PublicKey *public_key, uint8_t *out, size_t *out_len);
+
+// NULL is ignored.
+//
+// # Safety
+//
+// `public_key` must come from this library and not be used afterwards.
+void cncs_sm2_public_key_free(struct CncsSm2PublicKey *public_key);
+
+// Writes the 64 bytes of r || s. `user_id` NULL means the default, 1234567812345678.
+//
+// # Safety
+//
+// `private_key` must come from this library, `msg` be valid for `msg_len` bytes of reads,
+// `user_id` NULL or valid for `user_id_len` bytes of reads, `out` NULL or valid for
+// `*out_len` bytes of writes, `out_len` valid for reads and writes.
+int32_t cncs_sm2_sign(const struct CncsSm2PrivateKey *private_key, const uint8_t *msg, size_t msg_len, const uint8_t *user_id, size_t user_id_len, uint8_t *out, size_t *out_len);
+
+// `CNCS_SM2_OK` when the 64-byte r || s `signature` matches, `CNCS_SM2_ERR_VERIFICATION_FAILED`
+// when it does not.
+//
+// # Safety
+//
+// `public_key` must come from this library, `msg`, `user_id` and `signature` as in
+// `cncs_sm2_sign`.
+int32_t cncs_sm2_verify(const struct CncsSm2PublicKey *public_key, const uint8_t *msg, size_t msg_len, const uint8_t *user_id, size_t user_id_len, const uint8_t *signature, size_t signature_len);
+
+// 64-byte r || s to SEQUENCE { r INTEGER, s INTEGER }, at most 72 bytes.
+//
+// # Safety
+//
+// `signature` must be valid for `signature_len` bytes of reads, `out` NULL or valid for
+// `*out_len` bytes of writes, `out_len` valid for reads and writes.
+int32_t cncs_sm2_signature_to_der(const uint8_t *signature, size_t signature_len, uint8_t *out, size_t *out_len);
+
+// SEQUENCE { r INTEGER, s INTEGER } to 64-byte r || s.
+//
+// # Safety
+//
+// As `cncs_sm2_signature_to_der`.
+int32_t cncs_sm2_signature_from_der(const uint8_t *der, size_t der_len, uint8_t *out, size_t *out_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CNCS_SM2_H */
@@ -0,0 +1,179 @@
+use cncs_sm2_kit::{ciphertext_len, decrypt, encrypt};
+
+use crate::{
+    ffi_call, handle, input, input_mode, write_output, CncsSm2PrivateKey, CncsSm2PublicKey,
+    FfiError,
+};
+
+/// Length of the ciphertext of a `msg_len`-byte message, 97 bytes more.
+#[no_mangle]
+pub extern "C" fn cncs_sm2_ciphertext_len(msg_len: usize) -> usize {
+    ciphertext_len(msg_len)
+}
+
+/// Writes the 04-prefixed ciphertext, of `cncs_sm2_ciphertext_len(msg_len)` bytes, in the
+/// `CNCS_SM2_MODE_*` byte order `mode`.
+///
+/// # Safety
+///
+/// `public_key` must come from this library, `msg` be valid for `msg_len` bytes of reads,
+/// `out` NULL or valid for `*out_len` bytes of writes, `out_len` valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_encrypt(
+    public_key: *const CncsSm2PublicKey,
+    msg: *const u8,
+    msg_len: usize,
+    mode: i32,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let public_key = handle(public_key, "public_key")?;
+        let msg = input(msg, msg_len, "msg")?;
+        let mode = input_mode(mode)?;
+        // Checked first, so that a length query does not spend an encryption
+        write_capacity(ciphertext_len(msg.len()), out, out_len)?;
+        let ciphertext = encrypt(&public_key.0, msg, mode)?;
+        write_output(&ciphertext, out, out_len)
+    })
+}
+
+/// Writes the plaintext, 97 bytes shorter than the ciphertext.
+///
+/// # Safety
+///
+/// `private_key` must come from this library, `ciphertext` be valid for `ciphertext_len`
+/// bytes of reads, `out` NULL or valid for `*out_len` bytes of writes, `out_len` valid for
+/// reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_decrypt(
+    private_key: *const CncsSm2PrivateKey,
+    ciphertext: *const u8,
+    ciphertext_len: usize,
+    mode: i32,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let private_key = handle(private_key, "private_key")?;
+        let ciphertext = input(ciphertext, ciphertext_len, "ciphertext")?;
+        let mode = input_mode(mode)?;
+        if let Some(msg_len) = ciphertext.len().checked_sub(self::ciphertext_len(0)) {
+            write_capacity(msg_len, out, out_len)?;
+        }
+        let msg = decrypt(&private_key.0, ciphertext, mode)?;
+        write_output(&msg, out, out_len)
+    })
+}
+
+/// `write_output` of `len` bytes, without writing them.
+unsafe fn write_capacity(len: usize, out: *mut u8, out_len: *mut usize) -> Result<(), FfiError> {
+    if out_len.is_null() {
+        return Err(FfiError::NullPointer("out_len"));
+    }
+    if out.is_null() || *out_len < len {
+        *out_len = len;
+        return Err(cncs_sm2_kit::Error::BufferTooSmall(len).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::ptr;
+
+    use crate::{
+        cncs_sm2_private_key_free, cncs_sm2_private_key_generate, cncs_sm2_private_key_public_key,
+        cncs_sm2_public_key_free, CNCS_SM2_ERR_BUFFER_TOO_SMALL, CNCS_SM2_ERR_DECRYPTION,
+        CNCS_SM2_ERR_INVALID_ARGUMENT, CNCS_SM2_MODE_C1C2C3, CNCS_SM2_MODE_C1C3C2, CNCS_SM2_OK,
+    };
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        unsafe {
+            let mut private_key = ptr::null_mut();
+            assert_eq!(cncs_sm2_private_key_generate(&mut private_key), CNCS_SM2_OK);
+            let mut public_key = ptr::null_mut();
+            assert_eq!(
+                cncs_sm2_private_key_public_key(private_key, &mut public_key),
+                CNCS_SM2_OK
+            );
+
+            let msg = b"TEST";
+            for mode in [CNCS_SM2_MODE_C1C3C2, CNCS_SM2_MODE_C1C2C3] {
+                let mut len = 0;
+                assert_eq!(
+                    cncs_sm2_encrypt(
+                        public_key,
+                        msg.as_ptr(),
+                        msg.len(),
+                        mode,
+                        ptr::null_mut(),
+                        &mut len
+                    ),
+                    CNCS_SM2_ERR_BUFFER_TOO_SMALL
+                );
+                assert_eq!(len, cncs_sm2_ciphertext_len(msg.len()));
+
+                let mut ciphertext = vec![0; len];
+                assert_eq!(
+                    cncs_sm2_encrypt(
+                        public_key,
+                        msg.as_ptr(),
+                        msg.len(),
+                        mode,
+                        ciphertext.as_mut_ptr(),
+                        &mut len
+                    ),
+                    CNCS_SM2_OK
+                );
+
+                let mut plaintext = [0; 4];
+                let mut plaintext_len = plaintext.len();
+                assert_eq!(
+                    cncs_sm2_decrypt(
+                        private_key,
+                        ciphertext.as_ptr(),
+                        len,
+                        mode,
+                        plaintext.as_mut_ptr(),
+                        &mut plaintext_len
+                    ),
+                    CNCS_SM2_OK
+                );
+                assert_eq!(&plaintext[..plaintext_len], msg);
+
+                ciphertext[len - 1] ^= 1;
+                assert_eq!(
+                    cncs_sm2_decrypt(
+                        private_key,
+                        ciphertext.as_ptr(),
+                        len,
+                        mode,
+                        plaintext.as_mut_ptr(),
+                        &mut plaintext_len
+                    ),
+                    CNCS_SM2_ERR_DECRYPTION
+                );
+            }
+
+            let mut len = 0;
+            assert_eq!(
+                cncs_sm2_encrypt(
+                    public_key,
+                    msg.as_ptr(),
+                    msg.len(),
+                    2,
+                    ptr::null_mut(),
+                    &mut len
+                ),
+                CNCS_SM2_ERR_INVALID_ARGUMENT
+            );
+
+            cncs_sm2_public_key_free(public_key);
+            cncs_sm2_private_key_free(private_key);
+        }
+    }
+}
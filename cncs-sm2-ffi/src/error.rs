@@ -0,0 +1,175 @@
+use core::{ffi::c_char, fmt};
+use std::{
+    cell::RefCell,
+    ffi::CString,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use cncs_sm2_kit::Error;
+
+pub const CNCS_SM2_OK: i32 = 0;
+/// A required pointer argument is NULL.
+pub const CNCS_SM2_ERR_NULL_POINTER: i32 = -1;
+/// An unknown mode, or a string argument that is not UTF-8.
+pub const CNCS_SM2_ERR_INVALID_ARGUMENT: i32 = -2;
+/// Malformed hex, DER or PEM, or an input of the wrong length.
+pub const CNCS_SM2_ERR_PARSE: i32 = -3;
+pub const CNCS_SM2_ERR_INVALID_PRIVATE_KEY: i32 = -4;
+pub const CNCS_SM2_ERR_INVALID_PUBLIC_KEY: i32 = -5;
+pub const CNCS_SM2_ERR_INVALID_SIGNATURE: i32 = -6;
+/// Longer than 8191 bytes.
+pub const CNCS_SM2_ERR_INVALID_USER_ID: i32 = -7;
+/// Malformed or tampered ciphertext.
+pub const CNCS_SM2_ERR_DECRYPTION: i32 = -8;
+/// The output buffer is NULL or too small, `*out_len` holds the length needed.
+pub const CNCS_SM2_ERR_BUFFER_TOO_SMALL: i32 = -9;
+/// A well-formed signature that does not match.
+pub const CNCS_SM2_ERR_VERIFICATION_FAILED: i32 = -10;
+/// A bug, caught before it unwound into the caller.
+pub const CNCS_SM2_ERR_PANIC: i32 = -99;
+
+#[derive(Debug)]
+pub(crate) enum FfiError {
+    Kit(Error),
+    NullPointer(&'static str),
+    InvalidArgument(&'static str),
+    VerificationFailed,
+    Panic(String),
+}
+
+impl FfiError {
+    fn code(&self) -> i32 {
+        match self {
+            FfiError::Kit(err) => match err {
+                Error::ParseHex(_)
+                | Error::InvalidLength(_)
+                | Error::InvalidPrefix
+                | Error::Malformed => CNCS_SM2_ERR_PARSE,
+                Error::InvalidPrivateKey => CNCS_SM2_ERR_INVALID_PRIVATE_KEY,
+                Error::InvalidPublicKey => CNCS_SM2_ERR_INVALID_PUBLIC_KEY,
+                Error::InvalidSignature => CNCS_SM2_ERR_INVALID_SIGNATURE,
+                Error::InvalidUserId => CNCS_SM2_ERR_INVALID_USER_ID,
+                Error::InvalidCiphertext => CNCS_SM2_ERR_DECRYPTION,
+                Error::BufferTooSmall(_) => CNCS_SM2_ERR_BUFFER_TOO_SMALL,
+                // Nothing here does I/O
+                Error::Io(_) => CNCS_SM2_ERR_PANIC,
+            },
+            FfiError::NullPointer(_) => CNCS_SM2_ERR_NULL_POINTER,
+            FfiError::InvalidArgument(_) => CNCS_SM2_ERR_INVALID_ARGUMENT,
+            FfiError::VerificationFailed => CNCS_SM2_ERR_VERIFICATION_FAILED,
+            FfiError::Panic(_) => CNCS_SM2_ERR_PANIC,
+        }
+    }
+}
+
+impl fmt::Display for FfiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FfiError::Kit(err) => write!(f, "{}", err),
+            FfiError::NullPointer(name) => write!(f, "{} is NULL", name),
+            FfiError::InvalidArgument(msg) => write!(f, "{}", msg),
+            FfiError::VerificationFailed => write!(f, "signature does not match"),
+            FfiError::Panic(msg) => write!(f, "panicked: {}", msg),
+        }
+    }
+}
+
+impl From<Error> for FfiError {
+    fn from(err: Error) -> Self {
+        Self::Kit(err)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Runs the body of an exported function, turning errors and panics into codes.
+pub(crate) fn ffi_call(f: impl FnOnce() -> Result<(), FfiError>) -> i32 {
+    let result = catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let msg = if let Some(msg) = payload.downcast_ref::<&str>() {
+            (*msg).to_owned()
+        } else if let Some(msg) = payload.downcast_ref::<String>() {
+            msg.clone()
+        } else {
+            "unknown".to_owned()
+        };
+        Err(FfiError::Panic(msg))
+    });
+
+    match result {
+        Ok(()) => CNCS_SM2_OK,
+        Err(err) => {
+            let msg =
+                CString::new(err.to_string().replace('\0', " ")).expect("NUL bytes are replaced");
+            LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(msg));
+            err.code()
+        }
+    }
+}
+
+/// Copies the message of the last error on this thread, NUL-terminated, into `out`.
+///
+/// Returns `CNCS_SM2_ERR_BUFFER_TOO_SMALL` with the length needed, including the NUL, in
+/// `*out_len` when `out` is NULL or shorter. An empty message means no error so far.
+///
+/// # Safety
+///
+/// `out` must be NULL or valid for `*out_len` bytes of writes, `out_len` valid for reads and
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_last_error_message(out: *mut c_char, out_len: *mut usize) -> i32 {
+    let msg = LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map(|msg| msg.as_bytes_with_nul().to_vec())
+            .unwrap_or_else(|| vec![0])
+    });
+    // Not through ffi_call, which would overwrite the message being read
+    match crate::write_output(&msg, out.cast(), out_len) {
+        Ok(()) => CNCS_SM2_OK,
+        Err(err) => err.code(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::ptr;
+
+    fn last_error_message() -> String {
+        let mut buf = [0 as c_char; 256];
+        let mut len = buf.len();
+        assert_eq!(
+            unsafe { cncs_sm2_last_error_message(buf.as_mut_ptr(), &mut len) },
+            CNCS_SM2_OK
+        );
+        let bytes = buf[..len - 1].iter().map(|b| *b as u8).collect::<Vec<_>>();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_ffi_call() {
+        assert_eq!(last_error_message(), "");
+
+        assert_eq!(ffi_call(|| Ok(())), CNCS_SM2_OK);
+        assert_eq!(
+            ffi_call(|| Err(Error::InvalidLength(2).into())),
+            CNCS_SM2_ERR_PARSE
+        );
+        assert_eq!(last_error_message(), "invalid length: 2");
+
+        assert_eq!(ffi_call(|| panic!("boom")), CNCS_SM2_ERR_PANIC);
+        assert_eq!(last_error_message(), "panicked: boom");
+
+        // Too small, then the exact length
+        let mut len = 3;
+        assert_eq!(
+            unsafe { cncs_sm2_last_error_message(ptr::null_mut(), &mut len) },
+            CNCS_SM2_ERR_BUFFER_TOO_SMALL
+        );
+        assert_eq!(len, "panicked: boom".len() + 1);
+    }
+}
@@ -0,0 +1,373 @@
+use core::ffi::c_char;
+
+use cncs_sm2_kit::types::{PrivateKey, PublicKey};
+
+use crate::{ffi_call, handle, input, input_str, write_handle, write_output, FfiError};
+
+/// Opaque, free with `cncs_sm2_private_key_free`.
+pub struct CncsSm2PrivateKey(pub(crate) PrivateKey);
+
+/// Opaque, free with `cncs_sm2_public_key_free`.
+pub struct CncsSm2PublicKey(pub(crate) PublicKey);
+
+fn private_key(
+    private_key: Result<PrivateKey, cncs_sm2_kit::Error>,
+) -> Result<CncsSm2PrivateKey, FfiError> {
+    let private_key = private_key?;
+    private_key.validate()?;
+    Ok(CncsSm2PrivateKey(private_key))
+}
+
+fn public_key(
+    public_key: Result<PublicKey, cncs_sm2_kit::Error>,
+) -> Result<CncsSm2PublicKey, FfiError> {
+    let public_key = public_key?;
+    public_key.validate()?;
+    Ok(CncsSm2PublicKey(public_key))
+}
+
+//
+//
+//
+/// # Safety
+///
+/// `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_generate(out: *mut *mut CncsSm2PrivateKey) -> i32 {
+    ffi_call(|| {
+        let private_key = PrivateKey::random(&mut rand_core::OsRng);
+        write_handle(CncsSm2PrivateKey(private_key), out)
+    })
+}
+
+/// 64 hex characters, NUL-terminated.
+///
+/// # Safety
+///
+/// `hex` must be a NUL-terminated string, `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_from_hex(
+    hex: *const c_char,
+    out: *mut *mut CncsSm2PrivateKey,
+) -> i32 {
+    ffi_call(|| {
+        let hex = input_str(hex, "hex")?;
+        write_handle(private_key(PrivateKey::from_hex_str(hex))?, out)
+    })
+}
+
+/// 32 bytes, big-endian.
+///
+/// # Safety
+///
+/// `bytes` must be valid for `len` bytes of reads, `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_from_bytes(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut CncsSm2PrivateKey,
+) -> i32 {
+    ffi_call(|| {
+        let bytes = input(bytes, len, "bytes")?;
+        write_handle(private_key(PrivateKey::from_bytes(bytes))?, out)
+    })
+}
+
+/// PKCS#8, or SEC1 ECPrivateKey.
+///
+/// # Safety
+///
+/// `der` must be valid for `len` bytes of reads, `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_from_der(
+    der: *const u8,
+    len: usize,
+    out: *mut *mut CncsSm2PrivateKey,
+) -> i32 {
+    ffi_call(|| {
+        let der = input(der, len, "der")?;
+        write_handle(private_key(PrivateKey::from_der(der))?, out)
+    })
+}
+
+/// `PRIVATE KEY`, `EC PRIVATE KEY` or `SM2 PRIVATE KEY`, NUL-terminated.
+///
+/// # Safety
+///
+/// `pem` must be a NUL-terminated string, `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_from_pem(
+    pem: *const c_char,
+    out: *mut *mut CncsSm2PrivateKey,
+) -> i32 {
+    ffi_call(|| {
+        let pem = input_str(pem, "pem")?;
+        write_handle(private_key(PrivateKey::from_pem(pem))?, out)
+    })
+}
+
+/// Writes the 32 bytes of d.
+///
+/// # Safety
+///
+/// `private_key` must come from this library, `out` be NULL or valid for `*out_len` bytes of
+/// writes, `out_len` valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_to_bytes(
+    private_key: *const CncsSm2PrivateKey,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let private_key = handle(private_key, "private_key")?;
+        write_output(private_key.0.as_array(), out, out_len)
+    })
+}
+
+/// Writes PKCS#8 DER.
+///
+/// # Safety
+///
+/// As `cncs_sm2_private_key_to_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_to_der(
+    private_key: *const CncsSm2PrivateKey,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let private_key = handle(private_key, "private_key")?;
+        write_output(&private_key.0.to_der(), out, out_len)
+    })
+}
+
+/// # Safety
+///
+/// `private_key` must come from this library, `out` be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_public_key(
+    private_key: *const CncsSm2PrivateKey,
+    out: *mut *mut CncsSm2PublicKey,
+) -> i32 {
+    ffi_call(|| {
+        let private_key = handle(private_key, "private_key")?;
+        write_handle(CncsSm2PublicKey(private_key.0.public_key()), out)
+    })
+}
+
+/// NULL is ignored.
+///
+/// # Safety
+///
+/// `private_key` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_private_key_free(private_key: *mut CncsSm2PrivateKey) {
+    if !private_key.is_null() {
+        drop(Box::from_raw(private_key));
+    }
+}
+
+//
+//
+//
+/// x || y as 128 hex characters, with or without the 04 prefix, NUL-terminated.
+///
+/// # Safety
+///
+/// `hex` must be a NUL-terminated string, `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_public_key_from_hex(
+    hex: *const c_char,
+    out: *mut *mut CncsSm2PublicKey,
+) -> i32 {
+    ffi_call(|| {
+        let hex = input_str(hex, "hex")?;
+        write_handle(public_key(PublicKey::from_concated_hex_str(hex))?, out)
+    })
+}
+
+/// x || y, with or without the 04 prefix.
+///
+/// # Safety
+///
+/// `bytes` must be valid for `len` bytes of reads, `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_public_key_from_bytes(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut CncsSm2PublicKey,
+) -> i32 {
+    ffi_call(|| {
+        let bytes = input(bytes, len, "bytes")?;
+        write_handle(public_key(PublicKey::from_concated_bytes(bytes))?, out)
+    })
+}
+
+/// SubjectPublicKeyInfo.
+///
+/// # Safety
+///
+/// `der` must be valid for `len` bytes of reads, `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_public_key_from_der(
+    der: *const u8,
+    len: usize,
+    out: *mut *mut CncsSm2PublicKey,
+) -> i32 {
+    ffi_call(|| {
+        let der = input(der, len, "der")?;
+        write_handle(public_key(PublicKey::from_der(der))?, out)
+    })
+}
+
+/// `PUBLIC KEY`, NUL-terminated.
+///
+/// # Safety
+///
+/// `pem` must be a NUL-terminated string, `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_public_key_from_pem(
+    pem: *const c_char,
+    out: *mut *mut CncsSm2PublicKey,
+) -> i32 {
+    ffi_call(|| {
+        let pem = input_str(pem, "pem")?;
+        write_handle(public_key(PublicKey::from_pem(pem))?, out)
+    })
+}
+
+/// Writes the 64 bytes of x || y.
+///
+/// # Safety
+///
+/// `public_key` must come from this library, `out` be NULL or valid for `*out_len` bytes of
+/// writes, `out_len` valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_public_key_to_bytes(
+    public_key: *const CncsSm2PublicKey,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let public_key = handle(public_key, "public_key")?;
+        write_output(public_key.0.as_concated_array(), out, out_len)
+    })
+}
+
+/// Writes SubjectPublicKeyInfo DER.
+///
+/// # Safety
+///
+/// As `cncs_sm2_public_key_to_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_public_key_to_der(
+    public_key: *const CncsSm2PublicKey,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let public_key = handle(public_key, "public_key")?;
+        write_output(&public_key.0.to_der(), out, out_len)
+    })
+}
+
+/// NULL is ignored.
+///
+/// # Safety
+///
+/// `public_key` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_public_key_free(public_key: *mut CncsSm2PublicKey) {
+    if !public_key.is_null() {
+        drop(Box::from_raw(public_key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::{ffi::CStr, ptr};
+
+    use crate::{
+        CNCS_SM2_ERR_BUFFER_TOO_SMALL, CNCS_SM2_ERR_INVALID_PRIVATE_KEY, CNCS_SM2_ERR_NULL_POINTER,
+        CNCS_SM2_ERR_PARSE, CNCS_SM2_OK,
+    };
+
+    const PRIVATE_KEY: &CStr = c"7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D";
+    const PUBLIC_KEY: &CStr = c"FE1FC819D6A8827DB65BF1E114713CE68F116DAA3D6A75A3D8A5E48FAD68C5917F925FA394747EC86613C62EF4FD77E97BD8FF9744453CD4728CFE37F955183A";
+
+    #[test]
+    fn test_private_key() {
+        unsafe {
+            let mut private_key = ptr::null_mut();
+            assert_eq!(
+                cncs_sm2_private_key_from_hex(PRIVATE_KEY.as_ptr(), &mut private_key),
+                CNCS_SM2_OK
+            );
+
+            let mut der = [0; 256];
+            let mut der_len = der.len();
+            assert_eq!(
+                cncs_sm2_private_key_to_der(private_key, der.as_mut_ptr(), &mut der_len),
+                CNCS_SM2_OK
+            );
+            let mut parsed = ptr::null_mut();
+            assert_eq!(
+                cncs_sm2_private_key_from_der(der.as_ptr(), der_len, &mut parsed),
+                CNCS_SM2_OK
+            );
+            assert_eq!((*parsed).0, (*private_key).0);
+            cncs_sm2_private_key_free(parsed);
+
+            // Length query
+            let mut len = 0;
+            assert_eq!(
+                cncs_sm2_private_key_to_bytes(private_key, ptr::null_mut(), &mut len),
+                CNCS_SM2_ERR_BUFFER_TOO_SMALL
+            );
+            assert_eq!(len, 32);
+
+            let mut public_key = ptr::null_mut();
+            assert_eq!(
+                cncs_sm2_private_key_public_key(private_key, &mut public_key),
+                CNCS_SM2_OK
+            );
+            let mut expected = ptr::null_mut();
+            assert_eq!(
+                cncs_sm2_public_key_from_hex(PUBLIC_KEY.as_ptr(), &mut expected),
+                CNCS_SM2_OK
+            );
+            assert_eq!((*public_key).0, (*expected).0);
+
+            cncs_sm2_public_key_free(expected);
+            cncs_sm2_public_key_free(public_key);
+            cncs_sm2_private_key_free(private_key);
+            cncs_sm2_private_key_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        unsafe {
+            let mut private_key = ptr::null_mut();
+            assert_eq!(
+                cncs_sm2_private_key_from_hex(c"XYZ".as_ptr(), &mut private_key),
+                CNCS_SM2_ERR_PARSE
+            );
+            assert_eq!(
+                cncs_sm2_private_key_from_bytes([0; 32].as_ptr(), 32, &mut private_key),
+                CNCS_SM2_ERR_INVALID_PRIVATE_KEY
+            );
+            assert_eq!(
+                cncs_sm2_private_key_from_hex(ptr::null(), &mut private_key),
+                CNCS_SM2_ERR_NULL_POINTER
+            );
+            assert_eq!(
+                cncs_sm2_private_key_from_hex(PRIVATE_KEY.as_ptr(), ptr::null_mut()),
+                CNCS_SM2_ERR_NULL_POINTER
+            );
+            assert!(private_key.is_null());
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! C ABI of cncs-sm2-kit, see `include/cncs_sm2.h`.
+//!
+//! Every function returns `CNCS_SM2_OK` or a negative `CNCS_SM2_ERR_*` code, the message of
+//! which `cncs_sm2_last_error_message` retrieves. Variable-length outputs go to a caller
+//! buffer `out` whose capacity is passed in `*out_len`; on return `*out_len` holds the length
+//! written, or needed with `CNCS_SM2_ERR_BUFFER_TOO_SMALL`. `out` may be NULL to query it.
+
+use core::{
+    ffi::{c_char, CStr},
+    ptr, slice,
+};
+
+use cncs_sm2_kit::types::EncryptMode;
+
+pub mod cipher;
+pub mod error;
+pub mod key;
+pub mod signature;
+
+pub use self::{cipher::*, error::*, key::*, signature::*};
+
+pub const CNCS_SM2_MODE_C1C3C2: i32 = 0;
+pub const CNCS_SM2_MODE_C1C2C3: i32 = 1;
+
+pub const CNCS_SM2_PRIVATE_KEY_LEN: usize = 32;
+/// x || y, without the 04 prefix.
+pub const CNCS_SM2_PUBLIC_KEY_LEN: usize = 64;
+/// r || s.
+pub const CNCS_SM2_SIGNATURE_LEN: usize = 64;
+
+//
+//
+//
+unsafe fn input<'a>(ptr: *const u8, len: usize, name: &'static str) -> Result<&'a [u8], FfiError> {
+    if ptr.is_null() {
+        if len == 0 {
+            return Ok(&[]);
+        }
+        return Err(FfiError::NullPointer(name));
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn input_str<'a>(ptr: *const c_char, name: &'static str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::NullPointer(name));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| FfiError::InvalidArgument("string argument is not UTF-8"))
+}
+
+/// NULL for the default user id, 1234567812345678.
+unsafe fn input_user_id<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if ptr.is_null() {
+        return None;
+    }
+    Some(slice::from_raw_parts(ptr, len))
+}
+
+fn input_mode(mode: i32) -> Result<EncryptMode, FfiError> {
+    match mode {
+        CNCS_SM2_MODE_C1C3C2 => Ok(EncryptMode::C1C3C2),
+        CNCS_SM2_MODE_C1C2C3 => Ok(EncryptMode::C1C2C3),
+        _ => Err(FfiError::InvalidArgument("unknown mode")),
+    }
+}
+
+unsafe fn write_output(bytes: &[u8], out: *mut u8, out_len: *mut usize) -> Result<(), FfiError> {
+    if out_len.is_null() {
+        return Err(FfiError::NullPointer("out_len"));
+    }
+    let capacity = *out_len;
+    *out_len = bytes.len();
+    if out.is_null() || capacity < bytes.len() {
+        return Err(cncs_sm2_kit::Error::BufferTooSmall(bytes.len()).into());
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    Ok(())
+}
+
+unsafe fn write_handle<T>(value: T, out: *mut *mut T) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::NullPointer("out"));
+    }
+    *out = Box::into_raw(Box::new(value));
+    Ok(())
+}
+
+unsafe fn handle<'a, T>(ptr: *const T, name: &'static str) -> Result<&'a T, FfiError> {
+    ptr.as_ref().ok_or(FfiError::NullPointer(name))
+}
@@ -0,0 +1,101 @@
+use cncs_sm2_kit::{sign, types::Signature, verify};
+
+use crate::{
+    ffi_call, handle, input, input_user_id, write_output, CncsSm2PrivateKey, CncsSm2PublicKey,
+    FfiError,
+};
+
+/// Writes the 64 bytes of r || s. `user_id` NULL means the default, 1234567812345678.
+///
+/// # Safety
+///
+/// `private_key` must come from this library, `msg` be valid for `msg_len` bytes of reads,
+/// `user_id` NULL or valid for `user_id_len` bytes of reads, `out` NULL or valid for
+/// `*out_len` bytes of writes, `out_len` valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_sign(
+    private_key: *const CncsSm2PrivateKey,
+    msg: *const u8,
+    msg_len: usize,
+    user_id: *const u8,
+    user_id_len: usize,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let private_key = handle(private_key, "private_key")?;
+        let msg = input(msg, msg_len, "msg")?;
+        let user_id = input_user_id(user_id, user_id_len);
+        let signature = sign(&private_key.0, msg, user_id)?;
+        write_output(signature.as_concated_array(), out, out_len)
+    })
+}
+
+/// `CNCS_SM2_OK` when the 64-byte r || s `signature` matches, `CNCS_SM2_ERR_VERIFICATION_FAILED`
+/// when it does not.
+///
+/// # Safety
+///
+/// `public_key` must come from this library, `msg`, `user_id` and `signature` as in
+/// `cncs_sm2_sign`.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_verify(
+    public_key: *const CncsSm2PublicKey,
+    msg: *const u8,
+    msg_len: usize,
+    user_id: *const u8,
+    user_id_len: usize,
+    signature: *const u8,
+    signature_len: usize,
+) -> i32 {
+    ffi_call(|| {
+        let public_key = handle(public_key, "public_key")?;
+        let msg = input(msg, msg_len, "msg")?;
+        let user_id = input_user_id(user_id, user_id_len);
+        let signature =
+            Signature::from_concated_bytes(input(signature, signature_len, "signature")?)?;
+        if verify(&public_key.0, msg, user_id, &signature)? {
+            Ok(())
+        } else {
+            Err(FfiError::VerificationFailed)
+        }
+    })
+}
+
+/// 64-byte r || s to SEQUENCE { r INTEGER, s INTEGER }, at most 72 bytes.
+///
+/// # Safety
+///
+/// `signature` must be valid for `signature_len` bytes of reads, `out` NULL or valid for
+/// `*out_len` bytes of writes, `out_len` valid for reads and writes.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_signature_to_der(
+    signature: *const u8,
+    signature_len: usize,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let signature =
+            Signature::from_concated_bytes(input(signature, signature_len, "signature")?)?;
+        write_output(&signature.to_der(), out, out_len)
+    })
+}
+
+/// SEQUENCE { r INTEGER, s INTEGER } to 64-byte r || s.
+///
+/// # Safety
+///
+/// As `cncs_sm2_signature_to_der`.
+#[no_mangle]
+pub unsafe extern "C" fn cncs_sm2_signature_from_der(
+    der: *const u8,
+    der_len: usize,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let signature = Signature::from_der(input(der, der_len, "der")?)?;
+        write_output(signature.as_concated_array(), out, out_len)
+    })
+}
@@ -0,0 +1,61 @@
+//! Builds tests/c/test.c against include/cncs_sm2.h and the cdylib, then runs it on the shared
+//! fixtures/vectors.txt.
+
+#![cfg(unix)]
+
+use std::{
+    env,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+fn deps_dir() -> PathBuf {
+    // Next to this test, cargo puts the cdylib there under its plain name
+    let exe = env::current_exe().unwrap();
+    exe.parent().unwrap().to_owned()
+}
+
+#[test]
+fn test_c() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = deps_dir();
+    let out = lib_dir.join("cncs_sm2_ffi_test_c");
+
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    let status = match Command::new(&cc)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror"])
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg(crate_dir.join("tests").join("c").join("test.c"))
+        .arg("-o")
+        .arg(&out)
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lcncs_sm2_ffi")
+        .status()
+    {
+        Ok(status) => status,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            panic!("{} not found, install a C compiler or set CC", cc)
+        }
+        Err(err) => panic!("{}", err),
+    };
+    assert!(status.success(), "compiling tests/c/test.c failed");
+
+    // cargo test puts target/debug on LD_LIBRARY_PATH, which wins over the runpath and can
+    // hold a cdylib older than the one in deps
+    let vectors = crate_dir.join("..").join("fixtures").join("vectors.txt");
+    let output = Command::new(&out)
+        .arg(vectors)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(output.stdout, b"ok\n");
+}
@@ -0,0 +1,294 @@
+/* Built and run by tests/c.rs against the cdylib. */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "cncs_sm2.h"
+
+/* fixtures/vectors.txt, with the vectors of cncs-sm2-php/test.php and of OpenSSL. main reads it
+   from the path in argv[1], one NUL-terminated line after another. */
+static char vectors_txt[8192];
+static size_t vectors_len = 0;
+
+static const char *vector(const char *name) {
+    size_t name_len = strlen(name);
+    for (const char *line = vectors_txt; line < vectors_txt + vectors_len;
+         line += strlen(line) + 1) {
+        if (strncmp(line, name, name_len) == 0 && line[name_len] == ' ') {
+            return line + name_len + 1;
+        }
+    }
+    fprintf(stderr, "%s is not in the vectors\n", name);
+    exit(EXIT_FAILURE);
+}
+
+static void read_vectors(const char *path) {
+    FILE *file = fopen(path, "rb");
+    if (file == NULL) {
+        perror(path);
+        exit(EXIT_FAILURE);
+    }
+    size_t len = fread(vectors_txt, 1, sizeof(vectors_txt), file);
+    fclose(file);
+    if (len == sizeof(vectors_txt)) {
+        fprintf(stderr, "%s is too large\n", path);
+        exit(EXIT_FAILURE);
+    }
+
+    /* Unescapes \n in place, it only shortens the text */
+    for (size_t i = 0; i < len; i++) {
+        if (vectors_txt[i] == '\n') {
+            vectors_txt[vectors_len++] = '\0';
+        } else if (vectors_txt[i] == '\\' && i + 1 < len && vectors_txt[i + 1] == 'n') {
+            vectors_txt[vectors_len++] = '\n';
+            i++;
+        } else {
+            vectors_txt[vectors_len++] = vectors_txt[i];
+        }
+    }
+}
+
+#define MSG vector("MSG")
+#define USER_ID vector("USER_ID")
+#define PRIVATE_KEY vector("PRIVATE_KEY")
+#define PUBLIC_KEY vector("PUBLIC_KEY")
+#define SIGNATURE vector("SIGNATURE")
+#define SIGNATURE_WITH_USER_ID vector("SIGNATURE_WITH_USER_ID")
+#define CIPHERTEXT_C1C3C2 vector("CIPHERTEXT_C1C3C2")
+#define CIPHERTEXT_C1C2C3 vector("CIPHERTEXT_C1C2C3")
+#define OPENSSL_PRIVATE_KEY_PEM vector("OPENSSL_PRIVATE_KEY_PEM")
+#define OPENSSL_PUBLIC_KEY_PEM vector("OPENSSL_PUBLIC_KEY_PEM")
+/* With an empty user id */
+#define OPENSSL_SIGNATURE_DER vector("OPENSSL_SIGNATURE_DER")
+
+static int failures = 0;
+
+#define CHECK(cond)                                                                                \
+    do {                                                                                           \
+        if (!(cond)) {                                                                             \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #cond);                             \
+            failures++;                                                                            \
+        }                                                                                          \
+    } while (0)
+
+#define CHECK_CODE(expr, expected)                                                                 \
+    do {                                                                                           \
+        int32_t code_ = (expr);                                                                    \
+        if (code_ != (expected)) {                                                                 \
+            fprintf(stderr, "%s:%d: %s returned %d, %s\n", __FILE__, __LINE__, #expr, code_,       \
+                    last_error_message());                                                         \
+            failures++;                                                                            \
+        }                                                                                          \
+    } while (0)
+
+static const char *last_error_message(void) {
+    static char buf[256];
+    size_t len = sizeof(buf);
+    if (cncs_sm2_last_error_message(buf, &len) != CNCS_SM2_OK) {
+        return "(message too long)";
+    }
+    return buf;
+}
+
+static size_t from_hex(const char *hex, uint8_t *out) {
+    size_t len = strlen(hex) / 2;
+    for (size_t i = 0; i < len; i++) {
+        unsigned int byte;
+        sscanf(hex + 2 * i, "%2x", &byte);
+        out[i] = (uint8_t)byte;
+    }
+    return len;
+}
+
+static void test_sign_and_verify(void) {
+    CncsSm2PrivateKey *private_key = NULL;
+    CncsSm2PublicKey *public_key = NULL;
+    CHECK_CODE(cncs_sm2_private_key_from_hex(PRIVATE_KEY, &private_key), CNCS_SM2_OK);
+    CHECK_CODE(cncs_sm2_public_key_from_hex(PUBLIC_KEY, &public_key), CNCS_SM2_OK);
+
+    uint8_t signature[CNCS_SM2_SIGNATURE_LEN];
+    size_t signature_len = sizeof(signature);
+
+    /* The vectors, with the user id given and with NULL for the default */
+    from_hex(SIGNATURE_WITH_USER_ID, signature);
+    CHECK_CODE(cncs_sm2_verify(public_key, (const uint8_t *)MSG, strlen(MSG),
+                               (const uint8_t *)USER_ID, strlen(USER_ID), signature,
+                               signature_len),
+               CNCS_SM2_OK);
+    from_hex(SIGNATURE, signature);
+    CHECK_CODE(cncs_sm2_verify(public_key, (const uint8_t *)MSG, strlen(MSG), NULL, 0, signature,
+                               signature_len),
+               CNCS_SM2_OK);
+
+    /* Length query, then a round trip */
+    size_t len = 0;
+    CHECK_CODE(cncs_sm2_sign(private_key, (const uint8_t *)MSG, strlen(MSG), NULL, 0, NULL, &len),
+               CNCS_SM2_ERR_BUFFER_TOO_SMALL);
+    CHECK(len == CNCS_SM2_SIGNATURE_LEN);
+    CHECK_CODE(cncs_sm2_sign(private_key, (const uint8_t *)MSG, strlen(MSG), NULL, 0, signature,
+                             &signature_len),
+               CNCS_SM2_OK);
+    CHECK(signature_len == CNCS_SM2_SIGNATURE_LEN);
+    CHECK_CODE(cncs_sm2_verify(public_key, (const uint8_t *)MSG, strlen(MSG), NULL, 0, signature,
+                               signature_len),
+               CNCS_SM2_OK);
+    CHECK_CODE(cncs_sm2_verify(public_key, (const uint8_t *)"TEST2", 5, NULL, 0, signature,
+                               signature_len),
+               CNCS_SM2_ERR_VERIFICATION_FAILED);
+    CHECK(strcmp(last_error_message(), "signature does not match") == 0);
+    CHECK_CODE(cncs_sm2_verify(public_key, (const uint8_t *)MSG, strlen(MSG), NULL, 0, signature,
+                               signature_len - 1),
+               CNCS_SM2_ERR_PARSE);
+
+    /* User ids are bytes, not necessarily UTF-8 */
+    const uint8_t user_id[] = {0xff, 0x00};
+    signature_len = sizeof(signature);
+    CHECK_CODE(cncs_sm2_sign(private_key, (const uint8_t *)MSG, strlen(MSG), user_id,
+                             sizeof(user_id), signature, &signature_len),
+               CNCS_SM2_OK);
+    CHECK_CODE(cncs_sm2_verify(public_key, (const uint8_t *)MSG, strlen(MSG), user_id,
+                               sizeof(user_id), signature, signature_len),
+               CNCS_SM2_OK);
+
+    cncs_sm2_public_key_free(public_key);
+    cncs_sm2_private_key_free(private_key);
+}
+
+static void test_encrypt_and_decrypt(void) {
+    CncsSm2PrivateKey *private_key = NULL;
+    CncsSm2PublicKey *public_key = NULL;
+    CHECK_CODE(cncs_sm2_private_key_from_hex(PRIVATE_KEY, &private_key), CNCS_SM2_OK);
+    CHECK_CODE(cncs_sm2_private_key_public_key(private_key, &public_key), CNCS_SM2_OK);
+
+    uint8_t ciphertext[256];
+    size_t ciphertext_len;
+    uint8_t plaintext[16];
+    size_t plaintext_len;
+
+    /* The vectors */
+    const char *vectors[] = {CIPHERTEXT_C1C3C2, CIPHERTEXT_C1C2C3};
+    const int32_t modes[] = {CNCS_SM2_MODE_C1C3C2, CNCS_SM2_MODE_C1C2C3};
+    for (int i = 0; i < 2; i++) {
+        ciphertext_len = from_hex(vectors[i], ciphertext);
+        plaintext_len = sizeof(plaintext);
+        CHECK_CODE(cncs_sm2_decrypt(private_key, ciphertext, ciphertext_len, modes[i], plaintext,
+                                    &plaintext_len),
+                   CNCS_SM2_OK);
+        CHECK(plaintext_len == strlen(MSG) && memcmp(plaintext, MSG, plaintext_len) == 0);
+
+        /* Too small a buffer reports the plaintext length */
+        plaintext_len = 1;
+        CHECK_CODE(cncs_sm2_decrypt(private_key, ciphertext, ciphertext_len, modes[i], plaintext,
+                                    &plaintext_len),
+                   CNCS_SM2_ERR_BUFFER_TOO_SMALL);
+        CHECK(plaintext_len == strlen(MSG));
+    }
+
+    /* Round trip into a buffer of the queried length */
+    ciphertext_len = 0;
+    CHECK_CODE(cncs_sm2_encrypt(public_key, (const uint8_t *)MSG, strlen(MSG), CNCS_SM2_MODE_C1C3C2,
+                                NULL, &ciphertext_len),
+               CNCS_SM2_ERR_BUFFER_TOO_SMALL);
+    CHECK(ciphertext_len == cncs_sm2_ciphertext_len(strlen(MSG)));
+    uint8_t *buf = malloc(ciphertext_len);
+    CHECK_CODE(cncs_sm2_encrypt(public_key, (const uint8_t *)MSG, strlen(MSG), CNCS_SM2_MODE_C1C3C2,
+                                buf, &ciphertext_len),
+               CNCS_SM2_OK);
+    plaintext_len = sizeof(plaintext);
+    CHECK_CODE(cncs_sm2_decrypt(private_key, buf, ciphertext_len, CNCS_SM2_MODE_C1C3C2, plaintext,
+                                &plaintext_len),
+               CNCS_SM2_OK);
+    CHECK(plaintext_len == strlen(MSG) && memcmp(plaintext, MSG, plaintext_len) == 0);
+
+    buf[ciphertext_len - 1] ^= 1;
+    CHECK_CODE(cncs_sm2_decrypt(private_key, buf, ciphertext_len, CNCS_SM2_MODE_C1C3C2, plaintext,
+                                &plaintext_len),
+               CNCS_SM2_ERR_DECRYPTION);
+    free(buf);
+
+    CHECK_CODE(cncs_sm2_encrypt(public_key, (const uint8_t *)MSG, strlen(MSG), 2, NULL,
+                                &ciphertext_len),
+               CNCS_SM2_ERR_INVALID_ARGUMENT);
+
+    cncs_sm2_public_key_free(public_key);
+    cncs_sm2_private_key_free(private_key);
+}
+
+static void test_openssl(void) {
+    CncsSm2PrivateKey *private_key = NULL;
+    CncsSm2PublicKey *public_key = NULL;
+    CncsSm2PublicKey *derived = NULL;
+    CHECK_CODE(cncs_sm2_private_key_from_pem(OPENSSL_PRIVATE_KEY_PEM, &private_key), CNCS_SM2_OK);
+    CHECK_CODE(cncs_sm2_public_key_from_pem(OPENSSL_PUBLIC_KEY_PEM, &public_key), CNCS_SM2_OK);
+    CHECK_CODE(cncs_sm2_private_key_public_key(private_key, &derived), CNCS_SM2_OK);
+
+    uint8_t a[CNCS_SM2_PUBLIC_KEY_LEN], b[CNCS_SM2_PUBLIC_KEY_LEN];
+    size_t a_len = sizeof(a), b_len = sizeof(b);
+    CHECK_CODE(cncs_sm2_public_key_to_bytes(public_key, a, &a_len), CNCS_SM2_OK);
+    CHECK_CODE(cncs_sm2_public_key_to_bytes(derived, b, &b_len), CNCS_SM2_OK);
+    CHECK(a_len == b_len && memcmp(a, b, a_len) == 0);
+
+    uint8_t der[128];
+    size_t der_len = from_hex(OPENSSL_SIGNATURE_DER, der);
+    uint8_t signature[CNCS_SM2_SIGNATURE_LEN];
+    size_t signature_len = sizeof(signature);
+    CHECK_CODE(cncs_sm2_signature_from_der(der, der_len, signature, &signature_len), CNCS_SM2_OK);
+    CHECK_CODE(cncs_sm2_verify(public_key, (const uint8_t *)MSG, strlen(MSG), (const uint8_t *)"",
+                               0, signature, signature_len),
+               CNCS_SM2_OK);
+
+    uint8_t reencoded[128];
+    size_t reencoded_len = sizeof(reencoded);
+    CHECK_CODE(cncs_sm2_signature_to_der(signature, signature_len, reencoded, &reencoded_len),
+               CNCS_SM2_OK);
+    CHECK(reencoded_len == der_len && memcmp(reencoded, der, der_len) == 0);
+
+    cncs_sm2_public_key_free(derived);
+    cncs_sm2_public_key_free(public_key);
+    cncs_sm2_private_key_free(private_key);
+}
+
+static void test_errors(void) {
+    CncsSm2PrivateKey *private_key = NULL;
+    CHECK_CODE(cncs_sm2_private_key_from_hex("XYZ", &private_key), CNCS_SM2_ERR_PARSE);
+    CHECK_CODE(cncs_sm2_private_key_from_hex(NULL, &private_key), CNCS_SM2_ERR_NULL_POINTER);
+    CHECK(strcmp(last_error_message(), "hex is NULL") == 0);
+    CHECK_CODE(cncs_sm2_private_key_from_pem(OPENSSL_PUBLIC_KEY_PEM, &private_key),
+               CNCS_SM2_ERR_PARSE);
+    CHECK(private_key == NULL);
+
+    /* The message length query counts the NUL */
+    size_t len = 0;
+    CHECK_CODE(cncs_sm2_last_error_message(NULL, &len), CNCS_SM2_ERR_BUFFER_TOO_SMALL);
+    CHECK(len > 1);
+
+    size_t signature_len = CNCS_SM2_SIGNATURE_LEN;
+    uint8_t signature[CNCS_SM2_SIGNATURE_LEN];
+    CHECK_CODE(cncs_sm2_sign(NULL, (const uint8_t *)MSG, strlen(MSG), NULL, 0, signature,
+                             &signature_len),
+               CNCS_SM2_ERR_NULL_POINTER);
+
+    cncs_sm2_private_key_free(NULL);
+    cncs_sm2_public_key_free(NULL);
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s <fixtures/vectors.txt>\n", argv[0]);
+        return EXIT_FAILURE;
+    }
+    read_vectors(argv[1]);
+
+    test_sign_and_verify();
+    test_encrypt_and_decrypt();
+    test_openssl();
+    test_errors();
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return EXIT_FAILURE;
+    }
+    printf("ok\n");
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,29 @@
+//! `include/cncs_sm2.h` is generated by cbindgen, run with `CNCS_SM2_FFI_UPDATE_HEADER=1` to
+//! regenerate it.
+
+use std::{env, fs, path::Path};
+
+#[test]
+fn test_header() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .unwrap()
+        .write(&mut header);
+    let header = String::from_utf8(header).unwrap();
+
+    let path = crate_dir.join("include").join("cncs_sm2.h");
+    if env::var_os("CNCS_SM2_FFI_UPDATE_HEADER").is_some() {
+        fs::write(&path, &header).unwrap();
+    }
+    assert_eq!(
+        fs::read_to_string(&path).unwrap_or_default(),
+        header,
+        "include/cncs_sm2.h is out of date, rerun with CNCS_SM2_FFI_UPDATE_HEADER=1"
+    );
+}
@@ -1,7 +1,7 @@
 use cncs_sm2_kit::types::PrivateKey;
 
 fn main() {
-    let private_key = PrivateKey::random_via_libsm();
+    let private_key = PrivateKey::random(&mut rand::thread_rng());
     let public_key = private_key.public_key();
 
     println!("private_key: {}", private_key.to_hex_str());
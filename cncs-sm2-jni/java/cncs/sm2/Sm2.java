@@ -0,0 +1,252 @@
+package cncs.sm2;
+
+import java.nio.charset.StandardCharsets;
+import java.security.MessageDigest;
+import java.util.Arrays;
+
+/**
+ * SM2 over the native library cncs_sm2_jni, which must be on {@code java.library.path}.
+ *
+ * <p>Signatures are the 64 bytes of r || s, {@link #signatureToDer} and {@link
+ * #signatureFromDer} convert to and from the DER of BouncyCastle's {@code SM2Signer}. A null
+ * {@code userId} means the default, 1234567812345678. Errors are thrown as {@link
+ * Sm2Exception}.
+ */
+public final class Sm2 {
+    static {
+        System.loadLibrary("cncs_sm2_jni");
+    }
+
+    private Sm2() {}
+
+    /** Byte order of the ciphertext, C1C3C2 per GB/T 32918.4-2016, C1C2C3 per earlier drafts. */
+    public enum EncryptMode {
+        C1C3C2,
+        C1C2C3,
+    }
+
+    // No hashCode, a secret key has no business in a hash table
+    @SuppressWarnings("overrides")
+    public static final class PrivateKey {
+        private final byte[] bytes;
+
+        private PrivateKey(byte[] bytes) {
+            this.bytes = bytes;
+        }
+
+        public static PrivateKey generate() {
+            return new PrivateKey(nativePrivateKeyGenerate());
+        }
+
+        /** 32 bytes, big-endian. */
+        public static PrivateKey fromBytes(byte[] bytes) {
+            return new PrivateKey(nativePrivateKeyFromBytes(bytes));
+        }
+
+        public static PrivateKey fromHex(String hex) {
+            return new PrivateKey(nativePrivateKeyFromHex(hex));
+        }
+
+        /** PKCS#8, or SEC1 ECPrivateKey. */
+        public static PrivateKey fromDer(byte[] der) {
+            return new PrivateKey(nativePrivateKeyFromDer(der));
+        }
+
+        /** {@code PRIVATE KEY}, {@code EC PRIVATE KEY} or {@code SM2 PRIVATE KEY}. */
+        public static PrivateKey fromPem(String pem) {
+            return new PrivateKey(nativePrivateKeyFromPem(pem));
+        }
+
+        public byte[] toBytes() {
+            return bytes.clone();
+        }
+
+        /** PKCS#8. */
+        public byte[] toDer() {
+            return nativePrivateKeyToDer(bytes);
+        }
+
+        public String toPem() {
+            return nativePrivateKeyToPem(bytes);
+        }
+
+        public PublicKey publicKey() {
+            return new PublicKey(nativePublicKey(bytes));
+        }
+
+        /** Constant time. */
+        @Override
+        public boolean equals(Object other) {
+            return other instanceof PrivateKey
+                    && MessageDigest.isEqual(bytes, ((PrivateKey) other).bytes);
+        }
+
+        @Override
+        public String toString() {
+            return "PrivateKey";
+        }
+    }
+
+    public static final class PublicKey {
+        private final byte[] bytes;
+
+        private PublicKey(byte[] bytes) {
+            this.bytes = bytes;
+        }
+
+        /** x || y, with or without the 04 prefix. */
+        public static PublicKey fromBytes(byte[] bytes) {
+            return new PublicKey(nativePublicKeyFromBytes(bytes));
+        }
+
+        public static PublicKey fromHex(String hex) {
+            return new PublicKey(nativePublicKeyFromHex(hex));
+        }
+
+        /** SubjectPublicKeyInfo. */
+        public static PublicKey fromDer(byte[] der) {
+            return new PublicKey(nativePublicKeyFromDer(der));
+        }
+
+        public static PublicKey fromPem(String pem) {
+            return new PublicKey(nativePublicKeyFromPem(pem));
+        }
+
+        /** x || y, without the 04 prefix. */
+        public byte[] toBytes() {
+            return bytes.clone();
+        }
+
+        /** SubjectPublicKeyInfo. */
+        public byte[] toDer() {
+            return nativePublicKeyToDer(bytes);
+        }
+
+        public String toPem() {
+            return nativePublicKeyToPem(bytes);
+        }
+
+        @Override
+        public boolean equals(Object other) {
+            return other instanceof PublicKey && Arrays.equals(bytes, ((PublicKey) other).bytes);
+        }
+
+        @Override
+        public int hashCode() {
+            return Arrays.hashCode(bytes);
+        }
+
+        @Override
+        public String toString() {
+            StringBuilder hex = new StringBuilder("PublicKey(");
+            for (byte b : bytes) {
+                hex.append(String.format("%02X", b));
+            }
+            return hex.append(')').toString();
+        }
+    }
+
+    //
+    //
+    //
+    public static byte[] sign(PrivateKey privateKey, byte[] msg) {
+        return nativeSign(privateKey.bytes, msg, null);
+    }
+
+    /** {@code userId} as UTF-8. */
+    public static byte[] sign(PrivateKey privateKey, byte[] msg, String userId) {
+        return nativeSign(privateKey.bytes, msg, utf8(userId));
+    }
+
+    public static byte[] sign(PrivateKey privateKey, byte[] msg, byte[] userId) {
+        return nativeSign(privateKey.bytes, msg, userId);
+    }
+
+    public static boolean verify(PublicKey publicKey, byte[] msg, byte[] signature) {
+        return nativeVerify(publicKey.bytes, msg, null, signature);
+    }
+
+    /** {@code userId} as UTF-8. */
+    public static boolean verify(PublicKey publicKey, byte[] msg, String userId, byte[] signature) {
+        return nativeVerify(publicKey.bytes, msg, utf8(userId), signature);
+    }
+
+    public static boolean verify(PublicKey publicKey, byte[] msg, byte[] userId, byte[] signature) {
+        return nativeVerify(publicKey.bytes, msg, userId, signature);
+    }
+
+    private static byte[] utf8(String string) {
+        return string == null ? null : string.getBytes(StandardCharsets.UTF_8);
+    }
+
+    /** r || s to SEQUENCE { r INTEGER, s INTEGER }. */
+    public static byte[] signatureToDer(byte[] signature) {
+        return nativeSignatureToDer(signature);
+    }
+
+    /** SEQUENCE { r INTEGER, s INTEGER } to r || s. */
+    public static byte[] signatureFromDer(byte[] der) {
+        return nativeSignatureFromDer(der);
+    }
+
+    /** The 04-prefixed ciphertext, in C1C3C2 order. */
+    public static byte[] encrypt(PublicKey publicKey, byte[] msg) {
+        return encrypt(publicKey, msg, EncryptMode.C1C3C2);
+    }
+
+    public static byte[] encrypt(PublicKey publicKey, byte[] msg, EncryptMode mode) {
+        return nativeEncrypt(publicKey.bytes, msg, mode.ordinal());
+    }
+
+    public static byte[] decrypt(PrivateKey privateKey, byte[] ciphertext) {
+        return decrypt(privateKey, ciphertext, EncryptMode.C1C3C2);
+    }
+
+    public static byte[] decrypt(PrivateKey privateKey, byte[] ciphertext, EncryptMode mode) {
+        return nativeDecrypt(privateKey.bytes, ciphertext, mode.ordinal());
+    }
+
+    //
+    //
+    //
+    private static native byte[] nativePrivateKeyGenerate();
+
+    private static native byte[] nativePrivateKeyFromBytes(byte[] bytes);
+
+    private static native byte[] nativePrivateKeyFromHex(String hex);
+
+    private static native byte[] nativePrivateKeyFromDer(byte[] der);
+
+    private static native byte[] nativePrivateKeyFromPem(String pem);
+
+    private static native byte[] nativePrivateKeyToDer(byte[] privateKey);
+
+    private static native String nativePrivateKeyToPem(byte[] privateKey);
+
+    private static native byte[] nativePublicKey(byte[] privateKey);
+
+    private static native byte[] nativePublicKeyFromBytes(byte[] bytes);
+
+    private static native byte[] nativePublicKeyFromHex(String hex);
+
+    private static native byte[] nativePublicKeyFromDer(byte[] der);
+
+    private static native byte[] nativePublicKeyFromPem(String pem);
+
+    private static native byte[] nativePublicKeyToDer(byte[] publicKey);
+
+    private static native String nativePublicKeyToPem(byte[] publicKey);
+
+    private static native byte[] nativeSign(byte[] privateKey, byte[] msg, byte[] userId);
+
+    private static native boolean nativeVerify(
+            byte[] publicKey, byte[] msg, byte[] userId, byte[] signature);
+
+    private static native byte[] nativeSignatureToDer(byte[] signature);
+
+    private static native byte[] nativeSignatureFromDer(byte[] der);
+
+    private static native byte[] nativeEncrypt(byte[] publicKey, byte[] msg, int mode);
+
+    private static native byte[] nativeDecrypt(byte[] privateKey, byte[] ciphertext, int mode);
+}
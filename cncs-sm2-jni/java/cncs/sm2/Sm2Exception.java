@@ -0,0 +1,10 @@
+package cncs.sm2;
+
+/** Malformed input, a key or signature out of range, or a ciphertext that does not decrypt. */
+public class Sm2Exception extends RuntimeException {
+    private static final long serialVersionUID = 1L;
+
+    public Sm2Exception(String message) {
+        super(message);
+    }
+}
@@ -0,0 +1,423 @@
+//! JNI entry points of `cncs.sm2.Sm2`, see java/cncs/sm2/Sm2.java.
+//!
+//! Keys cross the boundary as raw bytes, d for private keys and x || y for public keys, which
+//! the Java side wraps in `Sm2.PrivateKey` and `Sm2.PublicKey`.
+
+use core::{fmt, ptr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use cncs_sm2_kit::{
+    decrypt, encrypt, sign,
+    types::{EncryptMode, PrivateKey, PublicKey, Signature},
+    verify, Error,
+};
+use jni::{
+    objects::{JByteArray, JClass, JString},
+    sys::{jboolean, jbyteArray, jint, jstring, JNI_FALSE, JNI_TRUE},
+    JNIEnv,
+};
+
+const SM2_EXCEPTION: &str = "cncs/sm2/Sm2Exception";
+
+enum JniError {
+    Kit(Error),
+    NullPointer(&'static str),
+    InvalidArgument(&'static str),
+    Jni(jni::errors::Error),
+}
+
+impl fmt::Display for JniError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JniError::Kit(err) => write!(f, "{}", err),
+            JniError::NullPointer(name) => write!(f, "{} is null", name),
+            JniError::InvalidArgument(msg) => write!(f, "{}", msg),
+            JniError::Jni(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<Error> for JniError {
+    fn from(err: Error) -> Self {
+        Self::Kit(err)
+    }
+}
+
+impl From<jni::errors::Error> for JniError {
+    fn from(err: jni::errors::Error) -> Self {
+        Self::Jni(err)
+    }
+}
+
+/// Runs the body of an entry point, throwing errors and panics as Java exceptions.
+fn run<'local, T>(
+    env: &mut JNIEnv<'local>,
+    default: T,
+    f: impl FnOnce(&mut JNIEnv<'local>) -> Result<T, JniError>,
+) -> T {
+    let err = match catch_unwind(AssertUnwindSafe(|| f(env))) {
+        Ok(Ok(value)) => return value,
+        Ok(Err(err)) => err,
+        Err(_) => {
+            let _ = env.throw_new("java/lang/IllegalStateException", "cncs-sm2-jni panicked");
+            return default;
+        }
+    };
+
+    // A Java exception is already pending
+    if matches!(err, JniError::Jni(jni::errors::Error::JavaException))
+        || env.exception_check().unwrap_or(true)
+    {
+        return default;
+    }
+    let class = match err {
+        JniError::Kit(_) | JniError::InvalidArgument(_) => SM2_EXCEPTION,
+        JniError::NullPointer(_) => "java/lang/NullPointerException",
+        JniError::Jni(_) => "java/lang/IllegalStateException",
+    };
+    let _ = env.throw_new(class, err.to_string());
+    default
+}
+
+//
+//
+//
+fn input_bytes(env: &JNIEnv, array: &JByteArray, name: &'static str) -> Result<Vec<u8>, JniError> {
+    if array.is_null() {
+        return Err(JniError::NullPointer(name));
+    }
+    Ok(env.convert_byte_array(array)?)
+}
+
+fn input_string(
+    env: &mut JNIEnv,
+    string: &JString,
+    name: &'static str,
+) -> Result<String, JniError> {
+    if string.is_null() {
+        return Err(JniError::NullPointer(name));
+    }
+    Ok(env.get_string(string)?.into())
+}
+
+/// null for the default user id, 1234567812345678.
+fn input_user_id(env: &JNIEnv, user_id: &JByteArray) -> Result<Option<Vec<u8>>, JniError> {
+    if user_id.is_null() {
+        return Ok(None);
+    }
+    input_bytes(env, user_id, "userId").map(Some)
+}
+
+fn input_private_key(env: &JNIEnv, private_key: &JByteArray) -> Result<PrivateKey, JniError> {
+    let private_key = PrivateKey::from_bytes(&input_bytes(env, private_key, "privateKey")?)?;
+    private_key.validate()?;
+    Ok(private_key)
+}
+
+fn input_public_key(env: &JNIEnv, public_key: &JByteArray) -> Result<PublicKey, JniError> {
+    let public_key = PublicKey::from_concated_bytes(&input_bytes(env, public_key, "publicKey")?)?;
+    public_key.validate()?;
+    Ok(public_key)
+}
+
+/// The ordinal of `Sm2.EncryptMode`.
+fn input_mode(mode: jint) -> Result<EncryptMode, JniError> {
+    match mode {
+        0 => Ok(EncryptMode::C1C3C2),
+        1 => Ok(EncryptMode::C1C2C3),
+        _ => Err(JniError::InvalidArgument("unknown mode")),
+    }
+}
+
+fn output_bytes(env: &JNIEnv, bytes: &[u8]) -> Result<jbyteArray, JniError> {
+    Ok(env.byte_array_from_slice(bytes)?.into_raw())
+}
+
+fn output_string(env: &JNIEnv, string: &str) -> Result<jstring, JniError> {
+    Ok(env.new_string(string)?.into_raw())
+}
+
+fn output_private_key(env: &JNIEnv, private_key: PrivateKey) -> Result<jbyteArray, JniError> {
+    private_key.validate()?;
+    output_bytes(env, private_key.as_array())
+}
+
+fn output_public_key(env: &JNIEnv, public_key: PublicKey) -> Result<jbyteArray, JniError> {
+    public_key.validate()?;
+    output_bytes(env, public_key.as_concated_array())
+}
+
+//
+//
+//
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePrivateKeyGenerate<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        output_private_key(env, PrivateKey::random(&mut rand_core::OsRng))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePrivateKeyFromBytes<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    bytes: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let private_key = input_private_key(env, &bytes)?;
+        output_bytes(env, private_key.as_array())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePrivateKeyFromHex<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    hex: JString<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let hex = input_string(env, &hex, "hex")?;
+        output_private_key(env, PrivateKey::from_hex_str(&hex)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePrivateKeyFromDer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    der: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let der = input_bytes(env, &der, "der")?;
+        output_private_key(env, PrivateKey::from_der(&der)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePrivateKeyFromPem<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pem: JString<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let pem = input_string(env, &pem, "pem")?;
+        output_private_key(env, PrivateKey::from_pem(&pem)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePrivateKeyToDer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    private_key: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let private_key = input_private_key(env, &private_key)?;
+        output_bytes(env, &private_key.to_der())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePrivateKeyToPem<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    private_key: JByteArray<'local>,
+) -> jstring {
+    run(&mut env, ptr::null_mut(), |env| {
+        let private_key = input_private_key(env, &private_key)?;
+        output_string(env, &private_key.to_pem())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePublicKey<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    private_key: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let private_key = input_private_key(env, &private_key)?;
+        output_bytes(env, private_key.public_key().as_concated_array())
+    })
+}
+
+//
+//
+//
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePublicKeyFromBytes<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    bytes: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let public_key = input_public_key(env, &bytes)?;
+        output_bytes(env, public_key.as_concated_array())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePublicKeyFromHex<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    hex: JString<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let hex = input_string(env, &hex, "hex")?;
+        output_public_key(env, PublicKey::from_concated_hex_str(&hex)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePublicKeyFromDer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    der: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let der = input_bytes(env, &der, "der")?;
+        output_public_key(env, PublicKey::from_der(&der)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePublicKeyFromPem<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    pem: JString<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let pem = input_string(env, &pem, "pem")?;
+        output_public_key(env, PublicKey::from_pem(&pem)?)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePublicKeyToDer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    public_key: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let public_key = input_public_key(env, &public_key)?;
+        output_bytes(env, &public_key.to_der())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativePublicKeyToPem<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    public_key: JByteArray<'local>,
+) -> jstring {
+    run(&mut env, ptr::null_mut(), |env| {
+        let public_key = input_public_key(env, &public_key)?;
+        output_string(env, &public_key.to_pem())
+    })
+}
+
+//
+//
+//
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativeSign<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    private_key: JByteArray<'local>,
+    msg: JByteArray<'local>,
+    user_id: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let private_key = input_private_key(env, &private_key)?;
+        let msg = input_bytes(env, &msg, "msg")?;
+        let user_id = input_user_id(env, &user_id)?;
+        let signature = sign(&private_key, &msg, user_id.as_deref())?;
+        output_bytes(env, signature.as_concated_array())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativeVerify<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    public_key: JByteArray<'local>,
+    msg: JByteArray<'local>,
+    user_id: JByteArray<'local>,
+    signature: JByteArray<'local>,
+) -> jboolean {
+    run(&mut env, JNI_FALSE, |env| {
+        let public_key = input_public_key(env, &public_key)?;
+        let msg = input_bytes(env, &msg, "msg")?;
+        let user_id = input_user_id(env, &user_id)?;
+        let signature =
+            Signature::from_concated_bytes(&input_bytes(env, &signature, "signature")?)?;
+        if verify(&public_key, &msg, user_id.as_deref(), &signature)? {
+            Ok(JNI_TRUE)
+        } else {
+            Ok(JNI_FALSE)
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativeSignatureToDer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    signature: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let signature =
+            Signature::from_concated_bytes(&input_bytes(env, &signature, "signature")?)?;
+        output_bytes(env, &signature.to_der())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativeSignatureFromDer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    der: JByteArray<'local>,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let signature = Signature::from_der(&input_bytes(env, &der, "der")?)?;
+        output_bytes(env, signature.as_concated_array())
+    })
+}
+
+//
+//
+//
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativeEncrypt<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    public_key: JByteArray<'local>,
+    msg: JByteArray<'local>,
+    mode: jint,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let public_key = input_public_key(env, &public_key)?;
+        let msg = input_bytes(env, &msg, "msg")?;
+        let ciphertext = encrypt(&public_key, &msg, input_mode(mode)?)?;
+        output_bytes(env, &ciphertext)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_cncs_sm2_Sm2_nativeDecrypt<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    private_key: JByteArray<'local>,
+    ciphertext: JByteArray<'local>,
+    mode: jint,
+) -> jbyteArray {
+    run(&mut env, ptr::null_mut(), |env| {
+        let private_key = input_private_key(env, &private_key)?;
+        let ciphertext = input_bytes(env, &ciphertext, "ciphertext")?;
+        let msg = decrypt(&private_key, &ciphertext, input_mode(mode)?)?;
+        output_bytes(env, &msg)
+    })
+}
@@ -0,0 +1,144 @@
+//! Compiles java/ and tests/java/ with javac, then runs them against the cdylib.
+//!
+//! With `BCPROV_JAR` set to a bcprov jar, signatures and ciphertexts are also cross-checked with
+//! BouncyCastle live. The ignored test_bouncycastle_vectors checks the committed BouncyCastle
+//! output in tests/java/bouncycastle-vectors.txt, `CNCS_SM2_JNI_UPDATE_VECTORS=1` regenerates it.
+
+#![cfg(unix)]
+
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+fn deps_dir() -> PathBuf {
+    // Next to this test, cargo puts the cdylib there under its plain name
+    let exe = env::current_exe().unwrap();
+    exe.parent().unwrap().to_owned()
+}
+
+fn java_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "java"))
+        .collect()
+}
+
+/// Compiles the wrapper and the tests, returns the directory of the cdylib and the classpath.
+/// Each test compiles into its own directory, as they run in parallel.
+fn compile(test: &str) -> (PathBuf, OsString) {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = deps_dir();
+    let classes_dir = lib_dir.join(format!("cncs_sm2_jni_{}_classes", test));
+    fs::create_dir_all(&classes_dir).unwrap();
+
+    let mut sources = java_files(&crate_dir.join("java").join("cncs").join("sm2"));
+    let test_dir = crate_dir
+        .join("tests")
+        .join("java")
+        .join("cncs")
+        .join("sm2");
+    sources.push(test_dir.join("Sm2Test.java"));
+    sources.push(test_dir.join("BouncyCastleVectorsTest.java"));
+    let mut classpath = OsString::from(&classes_dir);
+    if let Some(bcprov_jar) = env::var_os("BCPROV_JAR") {
+        sources.push(test_dir.join("BouncyCastleTest.java"));
+        classpath.push(":");
+        classpath.push(bcprov_jar);
+    }
+
+    // Java 8 for the wrapper, which the services still run
+    let status = match Command::new("javac")
+        .args(["--release", "8", "-Xlint:all", "-Xlint:-options", "-Werror"])
+        .arg("-cp")
+        .arg(&classpath)
+        .arg("-d")
+        .arg(&classes_dir)
+        .args(&sources)
+        .status()
+    {
+        Ok(status) => status,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            panic!("javac not found, install a JDK 8 or later")
+        }
+        Err(err) => panic!("{}", err),
+    };
+    assert!(status.success(), "javac failed");
+
+    (lib_dir, classpath)
+}
+
+fn run(lib_dir: &Path, classpath: &OsString, main: &str, args: &[&Path]) {
+    let output = Command::new("java")
+        .arg(format!("-Djava.library.path={}", lib_dir.display()))
+        .arg("-cp")
+        .arg(classpath)
+        .arg(main)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}: {}",
+        main,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(output.stdout, b"ok\n", "{}", main);
+}
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("fixtures")
+        .join("vectors.txt")
+}
+
+#[test]
+fn test_java() {
+    let (lib_dir, classpath) = compile("test_java");
+    run(&lib_dir, &classpath, "cncs.sm2.Sm2Test", &[&fixtures()]);
+    if env::var_os("BCPROV_JAR").is_some() {
+        run(&lib_dir, &classpath, "cncs.sm2.BouncyCastleTest", &[]);
+    }
+}
+
+#[test]
+#[ignore = "bouncycastle-vectors.txt is still empty, generate it with a bcprov jar"]
+fn test_bouncycastle_vectors() {
+    let (lib_dir, classpath) = compile("test_bouncycastle_vectors");
+    let vectors = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("java")
+        .join("bouncycastle-vectors.txt");
+    if env::var_os("CNCS_SM2_JNI_UPDATE_VECTORS").is_some() {
+        assert!(
+            env::var_os("BCPROV_JAR").is_some(),
+            "BCPROV_JAR is needed to regenerate"
+        );
+        let output = Command::new("java")
+            .arg("-cp")
+            .arg(&classpath)
+            .args(["cncs.sm2.BouncyCastleTest", "generate"])
+            .arg(fixtures())
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        fs::write(&vectors, output.stdout).unwrap();
+    }
+
+    run(
+        &lib_dir,
+        &classpath,
+        "cncs.sm2.BouncyCastleVectorsTest",
+        &[&fixtures(), &vectors],
+    );
+}
@@ -0,0 +1,117 @@
+package cncs.sm2;
+
+import java.math.BigInteger;
+import java.nio.charset.StandardCharsets;
+import java.security.SecureRandom;
+import java.util.Arrays;
+import org.bouncycastle.asn1.gm.GMNamedCurves;
+import org.bouncycastle.asn1.x9.X9ECParameters;
+import org.bouncycastle.crypto.engines.SM2Engine;
+import org.bouncycastle.crypto.params.ECDomainParameters;
+import org.bouncycastle.crypto.params.ECPrivateKeyParameters;
+import org.bouncycastle.crypto.params.ECPublicKeyParameters;
+import org.bouncycastle.crypto.params.ParametersWithRandom;
+import org.bouncycastle.crypto.signers.SM2Signer;
+
+/**
+ * Run by tests/java.rs when BCPROV_JAR points at a bcprov jar. With the arguments {@code
+ * generate} and the path of fixtures/vectors.txt it prints tests/java/bouncycastle-vectors.txt
+ * instead.
+ */
+public final class BouncyCastleTest {
+    static final X9ECParameters X9 = GMNamedCurves.getByName("sm2p256v1");
+    static final ECDomainParameters DOMAIN =
+            new ECDomainParameters(X9.getCurve(), X9.getG(), X9.getN(), X9.getH());
+
+    public static void main(String[] args) throws Exception {
+        if (args.length > 0 && args[0].equals("generate")) {
+            Sm2Test.readVectors(args[1]);
+            generate();
+            return;
+        }
+
+        X9ECParameters x9 = X9;
+        ECDomainParameters domain = DOMAIN;
+        SecureRandom random = new SecureRandom();
+        byte[] msg = "TEST".getBytes(StandardCharsets.UTF_8);
+
+        Sm2.PrivateKey privateKey = Sm2.PrivateKey.generate();
+        ECPrivateKeyParameters bcPrivateKey =
+                new ECPrivateKeyParameters(new BigInteger(1, privateKey.toBytes()), domain);
+        byte[] encoded = new byte[65];
+        encoded[0] = 0x04;
+        System.arraycopy(privateKey.publicKey().toBytes(), 0, encoded, 1, 64);
+        ECPublicKeyParameters bcPublicKey =
+                new ECPublicKeyParameters(x9.getCurve().decodePoint(encoded), domain);
+
+        for (int i = 0; i < 16; i++) {
+            // BouncyCastle signs, we verify
+            SM2Signer signer = new SM2Signer();
+            signer.init(true, new ParametersWithRandom(bcPrivateKey, random));
+            signer.update(msg, 0, msg.length);
+            byte[] der = signer.generateSignature();
+            Sm2Test.check(Sm2.verify(privateKey.publicKey(), msg, Sm2.signatureFromDer(der)));
+
+            // We sign, BouncyCastle verifies
+            byte[] signature = Sm2.signatureToDer(Sm2.sign(privateKey, msg));
+            SM2Signer verifier = new SM2Signer();
+            verifier.init(false, bcPublicKey);
+            verifier.update(msg, 0, msg.length);
+            Sm2Test.check(verifier.verifySignature(signature));
+
+            // Each way for encryption
+            SM2Engine engine = new SM2Engine(SM2Engine.Mode.C1C3C2);
+            engine.init(true, new ParametersWithRandom(bcPublicKey, random));
+            byte[] ciphertext = engine.processBlock(msg, 0, msg.length);
+            Sm2Test.check(Arrays.equals(Sm2.decrypt(privateKey, ciphertext), msg));
+
+            ciphertext = Sm2.encrypt(privateKey.publicKey(), msg);
+            engine = new SM2Engine(SM2Engine.Mode.C1C3C2);
+            engine.init(false, bcPrivateKey);
+            Sm2Test.check(Arrays.equals(engine.processBlock(ciphertext, 0, ciphertext.length), msg));
+        }
+        System.out.println("ok");
+    }
+
+    /** SM2Signer signatures and SM2Engine ciphertexts of MSG under PRIVATE_KEY of the vectors. */
+    static void generate() throws Exception {
+        SecureRandom random = new SecureRandom();
+        byte[] msg = Sm2Test.MSG;
+        BigInteger d = new BigInteger(Sm2Test.vector("PRIVATE_KEY"), 16);
+        ECPrivateKeyParameters privateKey = new ECPrivateKeyParameters(d, DOMAIN);
+        ECPublicKeyParameters publicKey =
+                new ECPublicKeyParameters(X9.getG().multiply(d).normalize(), DOMAIN);
+
+        System.out.println(
+                "# SM2Signer DER signatures and SM2Engine ciphertexts,"
+                        + " checked by BouncyCastleVectorsTest.");
+        System.out.println(
+                "# Regenerate with BCPROV_JAR=... CNCS_SM2_JNI_UPDATE_VECTORS=1"
+                        + " cargo test -- --ignored");
+        System.out.println(
+                "# Over MSG with PRIVATE_KEY of fixtures/vectors.txt:"
+                        + " <signature | C1C3C2 | C1C2C3> <hex>");
+        for (int i = 0; i < 4; i++) {
+            SM2Signer signer = new SM2Signer();
+            signer.init(true, new ParametersWithRandom(privateKey, random));
+            signer.update(msg, 0, msg.length);
+            System.out.println("signature " + toHex(signer.generateSignature()));
+        }
+        SM2Engine.Mode[] modes = {SM2Engine.Mode.C1C3C2, SM2Engine.Mode.C1C2C3};
+        for (SM2Engine.Mode mode : modes) {
+            for (int i = 0; i < 2; i++) {
+                SM2Engine engine = new SM2Engine(mode);
+                engine.init(true, new ParametersWithRandom(publicKey, random));
+                System.out.println(mode + " " + toHex(engine.processBlock(msg, 0, msg.length)));
+            }
+        }
+    }
+
+    static String toHex(byte[] bytes) {
+        StringBuilder builder = new StringBuilder();
+        for (byte b : bytes) {
+            builder.append(String.format("%02x", b));
+        }
+        return builder.toString();
+    }
+}
@@ -0,0 +1,41 @@
+package cncs.sm2;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Paths;
+import java.util.Arrays;
+
+/**
+ * Run by tests/java.rs with the paths of fixtures/vectors.txt and of bouncycastle-vectors.txt,
+ * checks the committed BouncyCastle output without a bcprov jar.
+ */
+public final class BouncyCastleVectorsTest {
+    public static void main(String[] args) throws IOException {
+        Sm2Test.readVectors(args[0]);
+        Sm2.PrivateKey privateKey = Sm2.PrivateKey.fromHex(Sm2Test.vector("PRIVATE_KEY"));
+        Sm2.PublicKey publicKey = privateKey.publicKey();
+
+        int count = 0;
+        for (String line : Files.readAllLines(Paths.get(args[1]), StandardCharsets.UTF_8)) {
+            if (line.isEmpty() || line.startsWith("#")) {
+                continue;
+            }
+            String[] parts = line.split(" ");
+            byte[] bytes = Sm2Test.hex(parts[1]);
+            if (parts[0].equals("signature")) {
+                byte[] signature = Sm2.signatureFromDer(bytes);
+                Sm2Test.check(Sm2.verify(publicKey, Sm2Test.MSG, signature));
+                Sm2Test.check(Arrays.equals(Sm2.signatureToDer(signature), bytes));
+            } else {
+                Sm2.EncryptMode mode = Sm2.EncryptMode.valueOf(parts[0]);
+                Sm2Test.check(Arrays.equals(Sm2.decrypt(privateKey, bytes, mode), Sm2Test.MSG));
+            }
+            count++;
+        }
+        if (count == 0) {
+            throw new AssertionError(args[1] + " has no vectors, regenerate it");
+        }
+        System.out.println("ok");
+    }
+}
@@ -0,0 +1,173 @@
+package cncs.sm2;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Paths;
+import java.util.Arrays;
+import java.util.HashMap;
+import java.util.Map;
+
+/**
+ * Run by tests/java.rs with the path of fixtures/vectors.txt.
+ */
+public final class Sm2Test {
+    static final byte[] MSG = "TEST".getBytes(StandardCharsets.UTF_8);
+
+    static final Map<String, String> VECTORS = new HashMap<>();
+
+    public static void main(String[] args) throws IOException {
+        readVectors(args[0]);
+        testKeys();
+        testSignAndVerify();
+        testOpenssl();
+        testEncryptAndDecrypt();
+        testErrors();
+        System.out.println("ok");
+    }
+
+    static void readVectors(String path) throws IOException {
+        for (String line : Files.readAllLines(Paths.get(path), StandardCharsets.UTF_8)) {
+            if (!line.isEmpty() && !line.startsWith("#")) {
+                String[] parts = line.split(" ", 2);
+                VECTORS.put(parts[0], parts[1].replace("\\n", "\n"));
+            }
+        }
+    }
+
+    static String vector(String name) {
+        String value = VECTORS.get(name);
+        if (value == null) {
+            throw new AssertionError(name + " is not in the vectors");
+        }
+        return value;
+    }
+
+    static void testKeys() {
+        Sm2.PrivateKey privateKey = Sm2.PrivateKey.fromHex(vector("PRIVATE_KEY"));
+        check(privateKey.publicKey().equals(Sm2.PublicKey.fromHex(vector("PUBLIC_KEY"))));
+        check(Sm2.PrivateKey.fromDer(privateKey.toDer()).equals(privateKey));
+        check(Sm2.PrivateKey.fromPem(privateKey.toPem()).equals(privateKey));
+        check(Sm2.PrivateKey.fromBytes(privateKey.toBytes()).equals(privateKey));
+
+        Sm2.PublicKey publicKey = Sm2.PublicKey.fromHex("04" + vector("PUBLIC_KEY"));
+        check(publicKey.toBytes().length == 64);
+        check(Sm2.PublicKey.fromDer(publicKey.toDer()).equals(publicKey));
+        check(Sm2.PublicKey.fromPem(publicKey.toPem()).equals(publicKey));
+        check(publicKey.toString().equals("PublicKey(" + vector("PUBLIC_KEY") + ")"));
+
+        Sm2.PrivateKey generated = Sm2.PrivateKey.generate();
+        check(!generated.equals(Sm2.PrivateKey.generate()));
+    }
+
+    static void testSignAndVerify() {
+        Sm2.PrivateKey privateKey = Sm2.PrivateKey.fromHex(vector("PRIVATE_KEY"));
+        Sm2.PublicKey publicKey = Sm2.PublicKey.fromHex(vector("PUBLIC_KEY"));
+
+        check(Sm2.verify(publicKey, MSG, hex(vector("SIGNATURE"))));
+        check(
+                Sm2.verify(
+                        publicKey, MSG, vector("USER_ID"), hex(vector("SIGNATURE_WITH_USER_ID"))));
+
+        byte[] signature = Sm2.sign(privateKey, MSG, "ALICE123@YAHOO.COM");
+        check(signature.length == 64);
+        check(Sm2.verify(publicKey, MSG, "ALICE123@YAHOO.COM", signature));
+        check(!Sm2.verify(publicKey, MSG, signature));
+        check(!Sm2.verify(publicKey, "TEST2".getBytes(StandardCharsets.UTF_8), signature));
+
+        // Z_A hashes bytes, so the user id need not be UTF-8
+        byte[] userId = {(byte) 0xFF, 0x00};
+        signature = Sm2.sign(privateKey, MSG, userId);
+        check(Sm2.verify(publicKey, MSG, userId, signature));
+        check(!Sm2.verify(publicKey, MSG, "ALICE123@YAHOO.COM", signature));
+
+        byte[] der = Sm2.signatureToDer(signature);
+        check(Arrays.equals(Sm2.signatureFromDer(der), signature));
+    }
+
+    static void testOpenssl() {
+        Sm2.PrivateKey privateKey = Sm2.PrivateKey.fromPem(vector("OPENSSL_PRIVATE_KEY_PEM"));
+        Sm2.PublicKey publicKey = Sm2.PublicKey.fromPem(vector("OPENSSL_PUBLIC_KEY_PEM"));
+        check(privateKey.publicKey().equals(publicKey));
+        check(privateKey.toPem().equals(vector("OPENSSL_PRIVATE_KEY_PEM")));
+        check(publicKey.toPem().equals(vector("OPENSSL_PUBLIC_KEY_PEM")));
+
+        byte[] der = hex(vector("OPENSSL_SIGNATURE_DER_WITH_USER_ID"));
+        byte[] signature = Sm2.signatureFromDer(der);
+        check(Sm2.verify(publicKey, MSG, signature));
+        check(Arrays.equals(Sm2.signatureToDer(signature), der));
+
+        check(
+                Sm2.verify(
+                        publicKey,
+                        MSG,
+                        "",
+                        Sm2.signatureFromDer(hex(vector("OPENSSL_SIGNATURE_DER")))));
+    }
+
+    static void testEncryptAndDecrypt() {
+        Sm2.PrivateKey privateKey = Sm2.PrivateKey.fromHex(vector("PRIVATE_KEY"));
+        Sm2.PublicKey publicKey = privateKey.publicKey();
+
+        check(Arrays.equals(Sm2.decrypt(privateKey, hex(vector("CIPHERTEXT_C1C3C2"))), MSG));
+        check(
+                Arrays.equals(
+                        Sm2.decrypt(
+                                privateKey,
+                                hex(vector("CIPHERTEXT_C1C2C3")),
+                                Sm2.EncryptMode.C1C2C3),
+                        MSG));
+
+        for (Sm2.EncryptMode mode : Sm2.EncryptMode.values()) {
+            byte[] ciphertext = Sm2.encrypt(publicKey, MSG, mode);
+            check(ciphertext.length == MSG.length + 97);
+            check(Arrays.equals(Sm2.decrypt(privateKey, ciphertext, mode), MSG));
+        }
+    }
+
+    static void testErrors() {
+        checkThrows(Sm2Exception.class, () -> Sm2.PrivateKey.fromHex("XYZ"));
+        checkThrows(Sm2Exception.class, () -> Sm2.PrivateKey.fromBytes(new byte[32]));
+        checkThrows(
+                Sm2Exception.class,
+                () -> Sm2.PublicKey.fromPem(vector("OPENSSL_PRIVATE_KEY_PEM")));
+        checkThrows(NullPointerException.class, () -> Sm2.PrivateKey.fromHex(null));
+
+        Sm2.PrivateKey privateKey = Sm2.PrivateKey.fromHex(vector("PRIVATE_KEY"));
+        byte[] ciphertext = Sm2.encrypt(privateKey.publicKey(), MSG);
+        ciphertext[ciphertext.length - 1] ^= 1;
+        checkThrows(Sm2Exception.class, () -> Sm2.decrypt(privateKey, ciphertext));
+        checkThrows(
+                Sm2Exception.class,
+                () -> Sm2.verify(privateKey.publicKey(), MSG, new byte[63]));
+    }
+
+    //
+    //
+    //
+    static byte[] hex(String hex) {
+        byte[] bytes = new byte[hex.length() / 2];
+        for (int i = 0; i < bytes.length; i++) {
+            bytes[i] = (byte) Integer.parseInt(hex.substring(2 * i, 2 * i + 2), 16);
+        }
+        return bytes;
+    }
+
+    static void check(boolean condition) {
+        if (!condition) {
+            throw new AssertionError("check failed");
+        }
+    }
+
+    static void checkThrows(Class<? extends Throwable> expected, Runnable f) {
+        try {
+            f.run();
+        } catch (Throwable e) {
+            if (expected.isInstance(e)) {
+                return;
+            }
+            throw new AssertionError("expected " + expected.getName() + ", got " + e, e);
+        }
+        throw new AssertionError("expected " + expected.getName());
+    }
+}
@@ -0,0 +1,55 @@
+//! The per-call setup that shared contexts remove, next to the operations that used to pay it.
+//!
+//! `cargo bench -p cncs-sm2-kit --bench context`
+
+use cncs_sm2_kit::{
+    decrypt, encrypt, sign,
+    types::{PrivateKey, PublicKey},
+    verify,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const PRIVATE_KEY: &str = "7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D";
+
+fn setup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("setup");
+    group.bench_function("libsm SigCtx::new", |b| {
+        b.iter(cncs_sm2_kit::libsm::sm2::signature::SigCtx::new)
+    });
+    group.bench_function("libsm EccCtx::new", |b| {
+        b.iter(cncs_sm2_kit::libsm::sm2::ecc::EccCtx::new)
+    });
+    group.bench_function("gmsm Sm2P256Curve::new", |b| {
+        b.iter(cncs_sm2_kit::gmsm::g2::p256::Sm2P256Curve::new)
+    });
+    group.finish();
+}
+
+fn operations(c: &mut Criterion) {
+    let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+    let public_key: PublicKey = private_key.public_key();
+    let msg = b"TEST";
+    let signature = sign(&private_key, msg, None).unwrap();
+    let ciphertext = encrypt(&public_key, msg, None).unwrap();
+
+    let mut group = c.benchmark_group("shared context");
+    group.bench_function("public_key", |b| {
+        b.iter(|| black_box(&private_key).public_key())
+    });
+    group.bench_function("sign", |b| {
+        b.iter(|| sign(black_box(&private_key), msg, None).unwrap())
+    });
+    group.bench_function("verify", |b| {
+        b.iter(|| verify(black_box(&public_key), msg, None, &signature).unwrap())
+    });
+    group.bench_function("encrypt", |b| {
+        b.iter(|| encrypt(black_box(&public_key), msg, None).unwrap())
+    });
+    group.bench_function("decrypt", |b| {
+        b.iter(|| decrypt(black_box(&private_key), &ciphertext, None).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, setup, operations);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+//! Every kit operation, for comparing backends and catching regressions against a saved
+//! baseline.
+//!
+//! `cargo bench -p cncs-sm2-kit --bench operations`
+//!
+//! To check a change or a libsm / gmsm upgrade, run with `-- --save-baseline before` on the
+//! previous revision and with `-- --baseline before` after it. Criterion then flags every
+//! benchmark outside the noise threshold, compare on an idle machine.
+
+use cncs_sm2_kit::{
+    decrypt, encrypt, sign,
+    types::{EncryptMode, PrivateKey, PublicKey},
+    verify,
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const PRIVATE_KEY: &str = "7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D";
+const USER_ID: &[u8] = b"ALICE123@YAHOO.COM";
+const MSG_LENS: [usize; 4] = [16, 1024, 16 * 1024, 1024 * 1024];
+
+fn key_generation(c: &mut Criterion) {
+    let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+    let mut group = c.benchmark_group("key generation");
+    group.bench_function("random", |b| {
+        b.iter(|| PrivateKey::random(&mut rand_core::OsRng))
+    });
+    group.bench_function("public_key", |b| {
+        b.iter(|| black_box(&private_key).public_key())
+    });
+    group.finish();
+}
+
+fn signature(c: &mut Criterion) {
+    let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+    let public_key: PublicKey = private_key.public_key();
+    let msg = b"TEST";
+
+    let mut group = c.benchmark_group("signature");
+    for user_id in [None, Some(USER_ID)] {
+        let label = if user_id.is_some() {
+            "user id"
+        } else {
+            "default id"
+        };
+        let signature = sign(&private_key, msg, user_id).unwrap();
+
+        group.bench_function(BenchmarkId::new("sign", label), |b| {
+            b.iter(|| sign(black_box(&private_key), msg, user_id).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("verify", label), |b| {
+            b.iter(|| verify(black_box(&public_key), msg, user_id, &signature).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn encryption(c: &mut Criterion) {
+    let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+    let public_key: PublicKey = private_key.public_key();
+
+    for mode in [EncryptMode::C1C3C2, EncryptMode::C1C2C3] {
+        let mut group = c.benchmark_group(format!("encryption {:?}", mode));
+        for msg_len in MSG_LENS {
+            let msg = vec![0x5A; msg_len];
+            let ciphertext = encrypt(&public_key, &msg, mode).unwrap();
+
+            group.throughput(Throughput::Bytes(msg_len as u64));
+            group.bench_with_input(BenchmarkId::new("encrypt", msg_len), &msg, |b, msg| {
+                b.iter(|| encrypt(black_box(&public_key), msg, mode).unwrap())
+            });
+            group.bench_with_input(
+                BenchmarkId::new("decrypt", msg_len),
+                &ciphertext,
+                |b, ciphertext| {
+                    b.iter(|| decrypt(black_box(&private_key), ciphertext, mode).unwrap())
+                },
+            );
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, key_generation, signature, encryption);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+//! Verification through libsm's two scalar multiplications, the BigUint fallback, the
+//! interleaved wNAF path behind `verify`, and a prepared `VerifyingKey`.
+//!
+//! `cargo bench -p cncs-sm2-kit --bench verify`
+
+use cncs_sm2_kit::{
+    libsm::sm2::{ecc::Point, signature},
+    sign,
+    types::{PrivateKey, PublicKey, VerifyingKey},
+    verify, verify_via_soft,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const PRIVATE_KEY: &str = "7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D";
+
+fn bench_verify(c: &mut Criterion) {
+    let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+    let public_key: PublicKey = private_key.public_key();
+    let msg = b"TEST";
+    let signature = sign(&private_key, msg, None).unwrap();
+
+    let sig_ctx = signature::SigCtx::new();
+    let libsm_public_key = Point::try_from(&public_key).unwrap();
+    let libsm_signature = signature::Signature::try_from(&signature).unwrap();
+    let verifying_key = VerifyingKey::new(&public_key).unwrap();
+
+    let mut group = c.benchmark_group("verify");
+    group.bench_function("libsm", |b| {
+        b.iter(|| sig_ctx.verify(msg, black_box(&libsm_public_key), &libsm_signature))
+    });
+    group.bench_function("verify_via_soft", |b| {
+        b.iter(|| verify_via_soft(black_box(&public_key), msg, None, &signature).unwrap())
+    });
+    group.bench_function("verify", |b| {
+        b.iter(|| verify(black_box(&public_key), msg, None, &signature).unwrap())
+    });
+    group.bench_function("VerifyingKey::verify", |b| {
+        b.iter(|| {
+            black_box(&verifying_key)
+                .verify(msg, None, &signature)
+                .unwrap()
+        })
+    });
+    group.bench_function("VerifyingKey::new", |b| {
+        b.iter(|| VerifyingKey::new(black_box(&public_key)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify);
+criterion_main!(benches);
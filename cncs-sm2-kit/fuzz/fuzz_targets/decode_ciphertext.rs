@@ -0,0 +1,40 @@
+//! The DER, hex and base64 ciphertext decoders, under every `DecodeOptions`.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use cncs_sm2_kit::types::{Ciphertext, CiphertextEncoding, DecodeOptions, EncryptMode};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+struct Input<'a> {
+    c1c2c3: bool,
+    encoding: u8,
+    prefix: Option<bool>,
+    bytes: &'a [u8],
+}
+
+fuzz_target!(|input: Input| {
+    if let Ok(ciphertext) = Ciphertext::from_der(input.bytes) {
+        assert_eq!(
+            Ciphertext::from_der(&ciphertext.to_der()).unwrap(),
+            ciphertext
+        );
+    }
+
+    let options = DecodeOptions {
+        mode: if input.c1c2c3 {
+            EncryptMode::C1C2C3
+        } else {
+            EncryptMode::C1C3C2
+        },
+        encoding: match input.encoding % 4 {
+            0 => CiphertextEncoding::Binary,
+            1 => CiphertextEncoding::UpperHex,
+            2 => CiphertextEncoding::LowerHex,
+            _ => CiphertextEncoding::Base64,
+        },
+        prefix: input.prefix,
+    };
+    let _ = Ciphertext::decode(input.bytes, &options);
+});
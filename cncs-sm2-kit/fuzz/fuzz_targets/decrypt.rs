@@ -0,0 +1,49 @@
+//! Every decryption path on random ciphertexts. None may panic, and all must agree.
+
+#![no_main]
+
+use std::io::Cursor;
+
+use arbitrary::Arbitrary;
+use cncs_sm2_kit::{
+    decrypt, decrypt_auto, decrypt_via_soft, stream,
+    types::{EncryptMode, PrivateKey},
+};
+use libfuzzer_sys::fuzz_target;
+
+const PRIVATE_KEY: &str = "7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D";
+
+#[derive(Debug, Arbitrary)]
+struct Input<'a> {
+    c1c2c3: bool,
+    ciphertext: &'a [u8],
+}
+
+fuzz_target!(|input: Input| {
+    let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+    let mode = if input.c1c2c3 {
+        EncryptMode::C1C2C3
+    } else {
+        EncryptMode::C1C3C2
+    };
+
+    let plaintext = decrypt(&private_key, input.ciphertext, mode).ok();
+    assert_eq!(
+        decrypt_via_soft(&private_key, input.ciphertext, mode).ok(),
+        plaintext
+    );
+
+    let mut streamed = vec![];
+    match stream::decrypt(
+        &private_key,
+        Cursor::new(input.ciphertext),
+        mode,
+        &mut streamed,
+    ) {
+        Ok(_) => assert_eq!(Some(streamed), plaintext),
+        // Nothing is written before C3 is checked
+        Err(_) => assert!(plaintext.is_none() && streamed.is_empty()),
+    }
+
+    let _ = decrypt_auto(&private_key, input.ciphertext);
+});
@@ -0,0 +1,35 @@
+//! Every byte parser, including the fixed-layout ciphertext readers.
+
+#![no_main]
+
+use cncs_sm2_kit::types::{Ciphertext, EncryptMode, PrivateKey, PublicKey, Signature};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &[u8]| {
+    if let Ok(private_key) = PrivateKey::from_bytes(input) {
+        assert_eq!(
+            PrivateKey::from_bytes(&private_key.to_bytes()).unwrap(),
+            private_key
+        );
+    }
+    if let Ok(public_key) = PublicKey::from_concated_bytes(input) {
+        assert_eq!(
+            PublicKey::from_concated_bytes(&public_key.to_concated_bytes()).unwrap(),
+            public_key
+        );
+    }
+    let _ = PublicKey::from_bytes(&input[..input.len() / 2], &input[input.len() / 2..]);
+    if let Ok(signature) = Signature::try_from(input) {
+        assert!(signature.validate().is_ok());
+    }
+    let _ = Signature::from_bytes(&input[..input.len() / 2], &input[input.len() / 2..]);
+
+    for mode in [EncryptMode::C1C2C3, EncryptMode::C1C3C2] {
+        if let Ok(ciphertext) = Ciphertext::from_bytes(input, mode) {
+            assert_eq!(ciphertext.to_bytes(mode), input);
+        }
+        if let Ok(ciphertext) = Ciphertext::from_bytes_without_prefix(input, mode) {
+            assert_eq!(ciphertext.to_bytes_without_prefix(mode), input);
+        }
+    }
+});
@@ -0,0 +1,39 @@
+//! The DER and PEM readers of keys and signatures, fed whatever a partner sends.
+
+#![no_main]
+
+use cncs_sm2_kit::types::{PrivateKey, PublicKey, Signature};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &[u8]| {
+    if let Ok(private_key) = PrivateKey::from_der(input) {
+        assert_eq!(
+            PrivateKey::from_der(&private_key.to_der()).unwrap(),
+            private_key
+        );
+    }
+    if let Ok(public_key) = PublicKey::from_der(input) {
+        assert_eq!(
+            PublicKey::from_der(&public_key.to_der()).unwrap(),
+            public_key
+        );
+    }
+    if let Ok(signature) = Signature::from_der(input) {
+        assert_eq!(Signature::from_der(&signature.to_der()).unwrap(), signature);
+    }
+
+    if let Ok(pem) = std::str::from_utf8(input) {
+        if let Ok(private_key) = PrivateKey::from_pem(pem) {
+            assert_eq!(
+                PrivateKey::from_pem(&private_key.to_pem()).unwrap(),
+                private_key
+            );
+        }
+        if let Ok(public_key) = PublicKey::from_pem(pem) {
+            assert_eq!(
+                PublicKey::from_pem(&public_key.to_pem()).unwrap(),
+                public_key
+            );
+        }
+    }
+});
@@ -0,0 +1,39 @@
+//! Every hex string parser, on arbitrary UTF-8 including multibyte characters.
+
+#![no_main]
+
+use cncs_sm2_kit::types::{PrivateKey, PublicKey, Signature};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(private_key) = PrivateKey::from_hex_str(input) {
+        assert_eq!(
+            PrivateKey::from_hex_str(&private_key.to_hex_str()).unwrap(),
+            private_key
+        );
+    }
+    if let Ok(public_key) = PublicKey::from_concated_hex_str(input) {
+        assert_eq!(public_key.to_concated_hex_str().len(), 128);
+        assert_eq!(
+            PublicKey::from_concated_hex_str(&public_key.to_concated_hex_str()).unwrap(),
+            public_key
+        );
+    }
+    if let Ok(signature) = Signature::from_concated_hex_str(input) {
+        assert_eq!(
+            Signature::from_concated_hex_str(&signature.to_concated_hex_str()).unwrap(),
+            signature
+        );
+    }
+    let _ = input.parse::<PublicKey>();
+    let _ = input.parse::<Signature>();
+
+    // The two-argument forms, split at every character boundary near the middle
+    let mid = input.len() / 2;
+    for at in mid.saturating_sub(2)..=mid + 2 {
+        if let (Some(a), Some(b)) = (input.get(..at), input.get(at..)) {
+            let _ = PublicKey::from_hex_str(a, b);
+            let _ = Signature::from_hex_str(a, b);
+        }
+    }
+});
@@ -0,0 +1,49 @@
+//! `verify` on random keys, messages, user ids and signatures, checked against the BigUint
+//! implementation.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use cncs_sm2_kit::{
+    types::{PublicKey, Signature, VerifyingKey},
+    verify, verify_strict, verify_via_soft,
+};
+use libfuzzer_sys::fuzz_target;
+
+const PUBLIC_KEY: &str = "FE1FC819D6A8827DB65BF1E114713CE68F116DAA3D6A75A3D8A5E48FAD68C5917F925FA394747EC86613C62EF4FD77E97BD8FF9744453CD4728CFE37F955183A";
+
+#[derive(Debug, Arbitrary)]
+struct Input<'a> {
+    /// Random coordinates are almost never on the curve, so mostly use the fixed key
+    public_key: Option<[u8; 64]>,
+    user_id: Option<&'a [u8]>,
+    signature: [u8; 64],
+    msg: &'a [u8],
+}
+
+fuzz_target!(|input: Input| {
+    let public_key = match input.public_key {
+        Some(bytes) => PublicKey::from_concated_bytes(&bytes).unwrap(),
+        None => PublicKey::from_concated_hex_str(PUBLIC_KEY).unwrap(),
+    };
+    let signature = Signature::new(
+        input.signature[..32].try_into().unwrap(),
+        input.signature[32..].try_into().unwrap(),
+    );
+
+    let ret = verify(&public_key, input.msg, input.user_id, &signature);
+    let expected = verify_via_soft(&public_key, input.msg, input.user_id, &signature);
+    assert_eq!(ret.as_ref().ok(), expected.as_ref().ok());
+    assert_eq!(
+        verify_strict(&public_key, input.msg, input.user_id, input.signature).is_ok(),
+        matches!(ret, Ok(true))
+    );
+    if let Ok(verifying_key) = VerifyingKey::new(&public_key) {
+        assert_eq!(
+            verifying_key
+                .verify(input.msg, input.user_id, &signature)
+                .ok(),
+            ret.ok()
+        );
+    }
+});
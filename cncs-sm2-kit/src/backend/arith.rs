@@ -0,0 +1,453 @@
+//! SM2 field, scalar and point arithmetic on 4 × 64-bit limbs.
+//!
+//! Field and scalar elements are kept in Montgomery form and points in projective coordinates
+//! with the complete formulas of Renes, Costello and Batina (2016) for a = -3. Nothing here
+//! branches on its inputs; `ct` and `vartime` build scalar multiplication on top.
+
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::curve;
+
+/// Little-endian 64-bit limbs.
+pub(crate) type Limbs = [u64; 4];
+
+pub(crate) const fn from_be_bytes(bytes: &[u8; 32]) -> Limbs {
+    let mut limbs = [0; 4];
+    let mut i = 0;
+    while i < 4 {
+        let mut j = 0;
+        while j < 8 {
+            limbs[i] = (limbs[i] << 8) | bytes[(3 - i) * 8 + j] as u64;
+            j += 1;
+        }
+        i += 1;
+    }
+    limbs
+}
+
+fn to_be_bytes(limbs: &Limbs) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (i, limb) in limbs.iter().enumerate() {
+        bytes[(3 - i) * 8..(4 - i) * 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+/// a + b + carry
+const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// a - b - borrow
+const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+/// a + b * c + carry
+const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+pub(crate) const fn sub(a: &Limbs, b: &Limbs) -> (Limbs, u64) {
+    let mut ret = [0; 4];
+    let mut borrow = 0;
+    let mut i = 0;
+    while i < 4 {
+        (ret[i], borrow) = sbb(a[i], b[i], borrow);
+        i += 1;
+    }
+    (ret, borrow)
+}
+
+/// `hi * 2^256 + a` reduced once by `m`, for values below 2m.
+const fn sub_if_ge(a: &Limbs, hi: u64, m: &Limbs) -> Limbs {
+    let (d, borrow) = sub(a, m);
+    let (_, borrow) = sbb(hi, 0, borrow);
+    // All ones if the subtraction underflowed, i.e. keep a
+    let mask = 0_u64.wrapping_sub(borrow);
+    let mut ret = [0; 4];
+    let mut i = 0;
+    while i < 4 {
+        ret[i] = d[i] ^ ((a[i] ^ d[i]) & mask);
+        i += 1;
+    }
+    ret
+}
+
+const fn add_mod(a: &Limbs, b: &Limbs, m: &Limbs) -> Limbs {
+    let mut ret = [0; 4];
+    let mut carry = 0;
+    let mut i = 0;
+    while i < 4 {
+        (ret[i], carry) = adc(a[i], b[i], carry);
+        i += 1;
+    }
+    sub_if_ge(&ret, carry, m)
+}
+
+const fn sub_mod(a: &Limbs, b: &Limbs, m: &Limbs) -> Limbs {
+    let (d, borrow) = sub(a, b);
+    let mask = 0_u64.wrapping_sub(borrow);
+    let mut ret = [0; 4];
+    let mut carry = 0;
+    let mut i = 0;
+    while i < 4 {
+        (ret[i], carry) = adc(d[i], m[i] & mask, carry);
+        i += 1;
+    }
+    ret
+}
+
+/// a * b / 2^256 mod m (CIOS), for a, b < m.
+const fn mont_mul(a: &Limbs, b: &Limbs, m: &Limbs, m_inv: u64) -> Limbs {
+    let mut t = [0; 4];
+    let mut t_4 = 0;
+    let mut i = 0;
+    while i < 4 {
+        let mut carry = 0;
+        let mut j = 0;
+        while j < 4 {
+            (t[j], carry) = mac(t[j], a[j], b[i], carry);
+            j += 1;
+        }
+        let (s, t_5) = adc(t_4, carry, 0);
+        t_4 = s;
+
+        let q = t[0].wrapping_mul(m_inv);
+        let (_, mut carry) = mac(t[0], q, m[0], 0);
+        let mut j = 1;
+        while j < 4 {
+            (t[j - 1], carry) = mac(t[j], q, m[j], carry);
+            j += 1;
+        }
+        let (s, c) = adc(t_4, carry, 0);
+        t[3] = s;
+        t_4 = t_5 + c;
+        i += 1;
+    }
+    sub_if_ge(&t, t_4, m)
+}
+
+//
+//
+//
+pub(crate) trait Modulus: Copy {
+    const M: Limbs;
+    /// -m^-1 mod 2^64
+    const M_INV: u64 = {
+        let mut inv = 1_u64;
+        let mut i = 0;
+        while i < 6 {
+            inv = inv.wrapping_mul(2_u64.wrapping_sub(Self::M[0].wrapping_mul(inv)));
+            i += 1;
+        }
+        inv.wrapping_neg()
+    };
+    /// 2^256 mod m, as m > 2^255
+    const R: Limbs = sub(&[0; 4], &Self::M).0;
+    /// 2^512 mod m
+    const R2: Limbs = {
+        let mut r = Self::R;
+        let mut i = 0;
+        while i < 256 {
+            r = add_mod(&r, &r, &Self::M);
+            i += 1;
+        }
+        r
+    };
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct P;
+impl Modulus for P {
+    const M: Limbs = from_be_bytes(&curve::P);
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct N;
+impl Modulus for N {
+    const M: Limbs = from_be_bytes(&curve::N);
+}
+
+/// An integer modulo `M` in Montgomery form.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Elem<M> {
+    limbs: Limbs,
+    _modulus: PhantomData<M>,
+}
+pub(crate) type FieldElement = Elem<P>;
+pub(crate) type Scalar = Elem<N>;
+
+impl<M: Modulus> Elem<M> {
+    pub(crate) const ZERO: Self = Self::new([0; 4]);
+    pub(crate) const ONE: Self = Self::new(M::R);
+
+    const fn new(limbs: Limbs) -> Self {
+        Self {
+            limbs,
+            _modulus: PhantomData,
+        }
+    }
+
+    /// Reduces a big-endian integer once, which covers every 256-bit value as m > 2^255.
+    pub(crate) const fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let limbs = sub_if_ge(&from_be_bytes(bytes), 0, &M::M);
+        Self::new(mont_mul(&limbs, &M::R2, &M::M, M::M_INV))
+    }
+
+    pub(crate) fn to_bytes(self) -> [u8; 32] {
+        to_be_bytes(&mont_mul(&self.limbs, &[1, 0, 0, 0], &M::M, M::M_INV))
+    }
+
+    pub(crate) fn is_zero(&self) -> Choice {
+        self.limbs.ct_eq(&[0; 4])
+    }
+
+    pub(crate) fn square(self) -> Self {
+        self * self
+    }
+
+    pub(crate) fn double(self) -> Self {
+        self + self
+    }
+
+    /// self^(m - 2), or zero for zero.
+    pub(crate) fn invert(self) -> Self {
+        // The exponent is public, so branching on its bits is fine
+        let exp = sub(&M::M, &[2, 0, 0, 0]).0;
+        let mut acc = Self::ONE;
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                acc = acc.square();
+                if (limb >> bit) & 1 == 1 {
+                    acc = acc * self;
+                }
+            }
+        }
+        acc
+    }
+}
+
+impl<M: Modulus> Add for Elem<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(add_mod(&self.limbs, &rhs.limbs, &M::M))
+    }
+}
+
+impl<M: Modulus> Sub for Elem<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(sub_mod(&self.limbs, &rhs.limbs, &M::M))
+    }
+}
+
+impl<M: Modulus> Neg for Elem<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl<M: Modulus> Mul for Elem<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(mont_mul(&self.limbs, &rhs.limbs, &M::M, M::M_INV))
+    }
+}
+
+impl<M: Modulus> ConditionallySelectable for Elem<M> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut limbs = [0; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            *limb = u64::conditional_select(&a.limbs[i], &b.limbs[i], choice);
+        }
+        Self::new(limbs)
+    }
+}
+
+impl<M: Modulus> ConstantTimeEq for Elem<M> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.limbs.ct_eq(&other.limbs)
+    }
+}
+
+//
+//
+//
+pub(crate) const B: FieldElement = FieldElement::from_bytes(&curve::B);
+
+/// (X : Y : Z) with x = X / Z and y = Y / Z, the identity is (0 : 1 : 0).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProjectivePoint {
+    pub(crate) x: FieldElement,
+    pub(crate) y: FieldElement,
+    pub(crate) z: FieldElement,
+}
+
+impl ProjectivePoint {
+    pub(crate) const IDENTITY: Self = Self {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ZERO,
+    };
+
+    pub(crate) const GENERATOR: Self = Self {
+        x: FieldElement::from_bytes(&curve::GX),
+        y: FieldElement::from_bytes(&curve::GY),
+        z: FieldElement::ONE,
+    };
+
+    pub(crate) fn from_affine(point: &[u8; 64]) -> Self {
+        Self {
+            x: FieldElement::from_bytes(crate::types::first_half(point)),
+            y: FieldElement::from_bytes(crate::types::second_half(point)),
+            z: FieldElement::ONE,
+        }
+    }
+
+    /// x || y, or zeros for the identity.
+    pub(crate) fn to_affine(self) -> [u8; 64] {
+        let z_inv = self.z.invert();
+        crate::types::concat(&(self.x * z_inv).to_bytes(), &(self.y * z_inv).to_bytes())
+    }
+
+    /// Algorithm 4 of RCB 2016, complete for any pair of points.
+    pub(crate) fn add(&self, rhs: &Self) -> Self {
+        let xx = self.x * rhs.x;
+        let yy = self.y * rhs.y;
+        let zz = self.z * rhs.z;
+        let xy_pairs = (self.x + self.y) * (rhs.x + rhs.y) - (xx + yy);
+        let yz_pairs = (self.y + self.z) * (rhs.y + rhs.z) - (yy + zz);
+        let xz_pairs = (self.x + self.z) * (rhs.x + rhs.z) - (xx + zz);
+
+        let bzz_part = xz_pairs - B * zz;
+        let bzz3_part = bzz_part.double() + bzz_part;
+        let yy_m_bzz3 = yy - bzz3_part;
+        let yy_p_bzz3 = yy + bzz3_part;
+
+        let zz3 = zz.double() + zz;
+        let bxz_part = B * xz_pairs - (zz3 + xx);
+        let bxz3_part = bxz_part.double() + bxz_part;
+        let xx3_m_zz3 = xx.double() + xx - zz3;
+
+        Self {
+            x: yy_p_bzz3 * xy_pairs - yz_pairs * bxz3_part,
+            y: yy_p_bzz3 * yy_m_bzz3 + xx3_m_zz3 * bxz3_part,
+            z: yy_m_bzz3 * yz_pairs + xy_pairs * xx3_m_zz3,
+        }
+    }
+
+    /// Algorithm 6 of RCB 2016.
+    pub(crate) fn double(&self) -> Self {
+        let xx = self.x.square();
+        let yy = self.y.square();
+        let zz = self.z.square();
+        let xy2 = (self.x * self.y).double();
+        let xz2 = (self.x * self.z).double();
+
+        let bzz_part = B * zz - xz2;
+        let bzz3_part = bzz_part.double() + bzz_part;
+        let yy_m_bzz3 = yy - bzz3_part;
+        let yy_p_bzz3 = yy + bzz3_part;
+        let y_frag = yy_p_bzz3 * yy_m_bzz3;
+        let x_frag = yy_m_bzz3 * xy2;
+
+        let zz3 = zz.double() + zz;
+        let bxz2_part = B * xz2 - (zz3 + xx);
+        let bxz6_part = bxz2_part.double() + bxz2_part;
+        let xx3_m_zz3 = xx.double() + xx - zz3;
+
+        let yz2 = (self.y * self.z).double();
+        Self {
+            x: x_frag - bxz6_part * yz2,
+            y: y_frag + xx3_m_zz3 * bxz6_part,
+            z: (yz2 * yy).double().double(),
+        }
+    }
+}
+
+impl Neg for ProjectivePoint {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+            z: self.z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num_bigint::BigUint;
+    use rand::{rngs::StdRng, RngCore as _, SeedableRng as _};
+
+    #[test]
+    fn test_field_matches_biguint() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let p = BigUint::from_bytes_be(&curve::P);
+        for _ in 0..200 {
+            let mut a = [0; 32];
+            let mut b = [0; 32];
+            rng.fill_bytes(&mut a);
+            rng.fill_bytes(&mut b);
+            let (a_big, b_big) = (
+                BigUint::from_bytes_be(&a) % &p,
+                BigUint::from_bytes_be(&b) % &p,
+            );
+            let (x, y) = (FieldElement::from_bytes(&a), FieldElement::from_bytes(&b));
+
+            let check = |elem: FieldElement, expected: BigUint| {
+                assert_eq!(BigUint::from_bytes_be(&elem.to_bytes()), expected);
+            };
+            check(x, a_big.to_owned());
+            check(x + y, (&a_big + &b_big) % &p);
+            check(x - y, (&a_big + &p - &b_big) % &p);
+            check(-x, (&p - &a_big) % &p);
+            check(x * y, (&a_big * &b_big) % &p);
+            check(x.invert(), a_big.modpow(&(&p - 2_u8), &p));
+        }
+
+        assert_eq!(FieldElement::from_bytes(&curve::P).to_bytes(), [0; 32]);
+        assert!(bool::from(FieldElement::ZERO.invert().is_zero()));
+    }
+
+    #[test]
+    fn test_scalar_matches_biguint() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let n = crate::curve::n();
+        for _ in 0..200 {
+            let mut a = [0; 32];
+            let mut b = [0; 32];
+            rng.fill_bytes(&mut a);
+            rng.fill_bytes(&mut b);
+            let (a_big, b_big) = (BigUint::from_bytes_be(&a), BigUint::from_bytes_be(&b));
+            let (x, y) = (Scalar::from_bytes(&a), Scalar::from_bytes(&b));
+
+            assert_eq!(
+                BigUint::from_bytes_be(&(x * y - x).to_bytes()),
+                ((&a_big * &b_big) % &n + &n - &a_big % &n) % &n
+            );
+            assert_eq!(
+                BigUint::from_bytes_be(&x.invert().to_bytes()),
+                (&a_big % &n).modpow(&(&n - 2_u8), &n)
+            );
+        }
+    }
+}
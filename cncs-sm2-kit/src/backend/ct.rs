@@ -0,0 +1,288 @@
+//! Constant-time scalar multiplication for secret scalars.
+//!
+//! Uses a fixed 4-bit window and scans the whole table, so neither the operations nor the
+//! memory accesses depend on the scalar.
+
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use super::arith::{from_be_bytes, sub, FieldElement, Modulus as _, ProjectivePoint, Scalar, N};
+
+impl ProjectivePoint {
+    /// [k]self with a fixed 4-bit window, k big-endian.
+    fn mul(&self, k: &[u8; 32]) -> Self {
+        let mut table = [Self::IDENTITY; 16];
+        table[1] = *self;
+        for i in 2..16 {
+            table[i] = if i % 2 == 0 {
+                table[i / 2].double()
+            } else {
+                table[i - 1].add(self)
+            };
+        }
+
+        let mut acc = Self::IDENTITY;
+        for byte in k {
+            for window in [byte >> 4, byte & 0x0F] {
+                acc = acc.double().double().double().double();
+
+                let mut selected = Self::IDENTITY;
+                for (i, point) in table.iter().enumerate() {
+                    selected.conditional_assign(point, (i as u8).ct_eq(&window));
+                }
+                acc = acc.add(&selected);
+            }
+        }
+        acc
+    }
+}
+
+impl ConditionallySelectable for ProjectivePoint {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: FieldElement::conditional_select(&a.x, &b.x, choice),
+            y: FieldElement::conditional_select(&a.y, &b.y, choice),
+            z: FieldElement::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+//
+//
+//
+/// [k]G as x || y, for k in [1, n - 1].
+pub(crate) fn g_mul(k: &[u8; 32]) -> [u8; 64] {
+    ProjectivePoint::GENERATOR.mul(k).to_affine()
+}
+
+/// [k]P as x || y, for k in [1, n - 1] and P on the curve.
+pub(crate) fn mul(k: &[u8; 32], point: &[u8; 64]) -> [u8; 64] {
+    ProjectivePoint::from_affine(point).mul(k).to_affine()
+}
+
+/// Samples k in [1, n - 1] with the same rejection rule as `curve::random_scalar`, so a
+/// seeded RNG yields the same k on every backend.
+pub(crate) fn random_scalar(rng: &mut (impl CryptoRng + RngCore)) -> [u8; 32] {
+    let mut k = [0; 32];
+    loop {
+        rng.fill_bytes(&mut k);
+        let limbs = from_be_bytes(&k);
+        let below_n = Choice::from(sub(&limbs, &N::M).1 as u8);
+        if bool::from(below_n & !limbs.ct_eq(&[0; 4])) {
+            return k;
+        }
+    }
+}
+
+/// r || s for the digest e, see GB/T 32918.2-2016 6.1.
+pub(crate) fn sign(rng: &mut (impl CryptoRng + RngCore), d: &[u8; 32], e: &[u8; 32]) -> [u8; 64] {
+    let d = Scalar::from_bytes(d);
+    let e = Scalar::from_bytes(e);
+    // (1 + d)^-1
+    let d_1_inv = (Scalar::ONE + d).invert();
+
+    loop {
+        let k_bytes = random_scalar(rng);
+        let k = Scalar::from_bytes(&k_bytes);
+        let x_1 = Scalar::from_bytes(crate::types::first_half(&g_mul(&k_bytes)));
+
+        // r = (e + x_1) mod n
+        let r = e + x_1;
+        if bool::from(r.is_zero() | (r + k).is_zero()) {
+            continue;
+        }
+
+        // s = (1 + d)^-1 * (k - r * d) mod n
+        let s = d_1_inv * (k - r * d);
+        if bool::from(s.is_zero()) {
+            continue;
+        }
+
+        return crate::types::concat(&r.to_bytes(), &s.to_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num_bigint::BigUint;
+    use rand::{rngs::StdRng, SeedableRng as _};
+
+    use crate::backend::soft::{AffinePoint, Curve};
+
+    fn to_affine_point(bytes: &[u8; 64]) -> AffinePoint {
+        AffinePoint {
+            x: BigUint::from_bytes_be(&bytes[..32]),
+            y: BigUint::from_bytes_be(&bytes[32..]),
+        }
+    }
+
+    #[test]
+    fn test_mul_matches_soft() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let curve = Curve::new();
+
+        let mut scalars = vec![[0; 32]; 3];
+        scalars[0][31] = 1;
+        scalars[1][31] = 2;
+        scalars[2] = crate::curve::N_MINUS_1;
+        scalars.extend((0..20).map(|_| random_scalar(&mut rng)));
+
+        let point = g_mul(&random_scalar(&mut rng));
+        for k in scalars {
+            let k_big = BigUint::from_bytes_be(&k);
+            assert_eq!(g_mul(&k), curve.g_mul(&k_big).unwrap().to_array());
+            assert_eq!(
+                mul(&k, &point),
+                curve
+                    .mul(&k_big, &to_affine_point(&point))
+                    .unwrap()
+                    .to_array()
+            );
+        }
+
+        // [n]G is the identity
+        assert_eq!(
+            ProjectivePoint::GENERATOR.mul(&crate::curve::N).to_affine(),
+            [0; 64]
+        );
+    }
+
+    #[test]
+    fn test_sign_matches_biguint() {
+        let curve = Curve::new();
+        let n = curve.n();
+        let d = BigUint::from_bytes_be(
+            &crate::types::PrivateKey::from_hex_str(crate::PRIVATE_KEY)
+                .unwrap()
+                .to_array(),
+        );
+
+        for seed in 0..10 {
+            let mut e = [0; 32];
+            StdRng::seed_from_u64(seed + 100).fill_bytes(&mut e);
+
+            let signature = sign(
+                &mut StdRng::seed_from_u64(seed),
+                &crate::types::to_bytes(&d),
+                &e,
+            );
+
+            let k = crate::curve::random_scalar(&mut StdRng::seed_from_u64(seed), n);
+            let r = (BigUint::from_bytes_be(&e) + curve.g_mul(&k).unwrap().x) % n;
+            let s = ((&d + 1_u8).modpow(&(n - 2_u8), n) * ((&k + n - (&r * &d) % n) % n)) % n;
+            assert_eq!(signature[..32], crate::types::to_bytes::<32>(&r));
+            assert_eq!(signature[32..], crate::types::to_bytes::<32>(&s));
+        }
+    }
+
+    /// Against libsm and gmsm, which only build with `std`.
+    #[cfg(feature = "std")]
+    mod libsm_gmsm {
+        use super::*;
+
+        /// 1, n - 2, n - 1 and random scalars.
+        fn scalars(seed: u64) -> Vec<[u8; 32]> {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut scalars = vec![[0; 32]; 3];
+            scalars[0][31] = 1;
+            scalars[1] = crate::types::to_bytes(&(crate::curve::n() - 2_u8));
+            scalars[2] = crate::curve::N_MINUS_1;
+            scalars.extend((0..20).map(|_| random_scalar(&mut rng)));
+            scalars
+        }
+
+        /// Hands out the same k on every draw.
+        struct FixedRng([u8; 32]);
+        impl RngCore for FixedRng {
+            fn next_u32(&mut self) -> u32 {
+                unimplemented!()
+            }
+            fn next_u64(&mut self) -> u64 {
+                unimplemented!()
+            }
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                dest.copy_from_slice(&self.0);
+            }
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+        impl CryptoRng for FixedRng {}
+
+        #[test]
+        fn test_g_mul_matches_libsm_and_gmsm() {
+            let ecc_ctx = crate::context::ecc_ctx();
+            let sm2_p256 = crate::context::sm2_p256();
+
+            for k in scalars(3) {
+                let expected = g_mul(&k);
+
+                let point = ecc_ctx.g_mul(&BigUint::from_bytes_be(&k));
+                assert_eq!(ecc_ctx.point_to_bytes(&point, false)[1..], expected);
+                let (x, y) = sm2_p256.scalar_base_mult(k.to_vec());
+                assert_eq!(AffinePoint { x, y }.to_array(), expected);
+            }
+        }
+
+        #[test]
+        fn test_ecdh_matches_libsm_and_gmsm() {
+            let ecc_ctx = crate::context::ecc_ctx();
+            let sm2_p256 = crate::context::sm2_p256();
+
+            let point = g_mul(&random_scalar(&mut StdRng::seed_from_u64(4)));
+            let libsm_point = ecc_ctx
+                .bytes_to_point(&[&[0x04], point.as_slice()].concat())
+                .unwrap();
+            let affine_point = to_affine_point(&point);
+
+            for k in scalars(5) {
+                // x2 || y2 of encryption and decryption
+                let expected = mul(&k, &point);
+
+                let x2y2 = ecc_ctx.mul(&BigUint::from_bytes_be(&k), &libsm_point);
+                assert_eq!(ecc_ctx.point_to_bytes(&x2y2, false)[1..], expected);
+                let (x, y) = sm2_p256.scalar_mult(
+                    affine_point.x.clone(),
+                    affine_point.y.clone(),
+                    k.to_vec(),
+                );
+                assert_eq!(AffinePoint { x, y }.to_array(), expected);
+            }
+        }
+
+        #[test]
+        fn test_sign_matches_libsm_and_gmsm() {
+            let sig_ctx = libsm::sm2::signature::SigCtx::new();
+            let ecc_ctx = crate::context::ecc_ctx();
+            let sm2_p256 = crate::context::sm2_p256();
+            let n = crate::curve::n();
+
+            let mut e = [0; 32];
+            StdRng::seed_from_u64(6).fill_bytes(&mut e);
+
+            // n - 1 is no private key
+            let keys = scalars(7)
+                .into_iter()
+                .filter(|d| *d != crate::curve::N_MINUS_1);
+            for (d, k) in keys.zip(scalars(8)) {
+                let signature = sign(&mut FixedRng(k), &d, &e);
+
+                // r = (e + x_1) mod n, x_1 from gmsm
+                let (x_1, _) = sm2_p256.scalar_base_mult(k.to_vec());
+                let r = (BigUint::from_bytes_be(&e) + x_1) % &n;
+                assert_eq!(signature[..32], crate::types::to_bytes::<32>(&r));
+
+                let pk = ecc_ctx.g_mul(&BigUint::from_bytes_be(&d));
+                let signature = crate::types::Signature::from_concated_bytes(&signature).unwrap();
+                assert!(sig_ctx.verify_raw(
+                    &e,
+                    &pk,
+                    &libsm::sm2::signature::Signature::try_from(&signature).unwrap()
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub(crate) mod arith;
+pub(crate) mod ct;
+pub(crate) mod soft;
+pub(crate) mod vartime;
@@ -0,0 +1,285 @@
+//! Portable SM2 curve arithmetic on top of `BigUint`, usable without `std`.
+
+use alloc::borrow::ToOwned as _;
+
+use num_bigint::BigUint;
+use num_traits::{One as _, Zero as _};
+
+use crate::curve;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AffinePoint {
+    pub(crate) x: BigUint,
+    pub(crate) y: BigUint,
+}
+
+impl AffinePoint {
+    pub(crate) fn to_array(&self) -> [u8; 64] {
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(&crate::types::to_bytes::<32>(&self.x));
+        bytes[32..].copy_from_slice(&crate::types::to_bytes::<32>(&self.y));
+        bytes
+    }
+}
+
+#[derive(Debug, Clone)]
+struct JacobianPoint {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+}
+impl JacobianPoint {
+    fn infinity() -> Self {
+        Self {
+            x: BigUint::one(),
+            y: BigUint::one(),
+            z: BigUint::zero(),
+        }
+    }
+
+    fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+}
+
+pub(crate) struct Curve {
+    p: BigUint,
+    a: BigUint,
+    b: BigUint,
+    n: BigUint,
+    g: AffinePoint,
+}
+
+impl Curve {
+    pub(crate) fn new() -> Self {
+        Self {
+            p: BigUint::from_bytes_be(&curve::P),
+            a: BigUint::from_bytes_be(&curve::A),
+            b: BigUint::from_bytes_be(&curve::B),
+            n: BigUint::from_bytes_be(&curve::N),
+            g: AffinePoint {
+                x: BigUint::from_bytes_be(&curve::GX),
+                y: BigUint::from_bytes_be(&curve::GY),
+            },
+        }
+    }
+
+    pub(crate) fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    pub(crate) fn g(&self) -> &AffinePoint {
+        &self.g
+    }
+
+    pub(crate) fn is_on_curve(&self, point: &AffinePoint) -> bool {
+        let AffinePoint { x, y } = point;
+        if x >= &self.p || y >= &self.p {
+            return false;
+        }
+
+        // y^2 = x^3 + a * x + b
+        let lhs = self.mul_mod(y, y);
+        let rhs = self.add_mod(
+            &self.add_mod(
+                &self.mul_mod(&self.mul_mod(x, x), x),
+                &self.mul_mod(&self.a, x),
+            ),
+            &self.b,
+        );
+        lhs == rhs
+    }
+
+    // With `constant-time`, secret scalars go to `ct` and these remain its test reference
+    #[cfg_attr(feature = "constant-time", allow(dead_code))]
+    pub(crate) fn g_mul(&self, k: &BigUint) -> Option<AffinePoint> {
+        self.mul(k, &self.g)
+    }
+
+    #[cfg_attr(feature = "constant-time", allow(dead_code))]
+    pub(crate) fn mul(&self, k: &BigUint, point: &AffinePoint) -> Option<AffinePoint> {
+        self.to_affine(&self.mul_jacobian(k, &self.to_jacobian(point)))
+    }
+
+    /// k1 * G + k2 * P
+    pub(crate) fn g_mul_add(
+        &self,
+        k1: &BigUint,
+        k2: &BigUint,
+        point: &AffinePoint,
+    ) -> Option<AffinePoint> {
+        let p1 = self.mul_jacobian(k1, &self.to_jacobian(&self.g));
+        let p2 = self.mul_jacobian(k2, &self.to_jacobian(point));
+        self.to_affine(&self.add(&p1, &p2))
+    }
+
+    //
+    fn add_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a + b) % &self.p
+    }
+
+    fn sub_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a + &self.p - b) % &self.p
+    }
+
+    fn mul_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % &self.p
+    }
+
+    fn inv_mod(&self, a: &BigUint) -> BigUint {
+        a.modpow(&(&self.p - 2_u8), &self.p)
+    }
+
+    //
+    fn to_jacobian(&self, point: &AffinePoint) -> JacobianPoint {
+        JacobianPoint {
+            x: point.x.to_owned(),
+            y: point.y.to_owned(),
+            z: BigUint::one(),
+        }
+    }
+
+    fn to_affine(&self, point: &JacobianPoint) -> Option<AffinePoint> {
+        if point.is_infinity() {
+            return None;
+        }
+
+        let z_inv = self.inv_mod(&point.z);
+        let z_inv_2 = self.mul_mod(&z_inv, &z_inv);
+        let z_inv_3 = self.mul_mod(&z_inv_2, &z_inv);
+        Some(AffinePoint {
+            x: self.mul_mod(&point.x, &z_inv_2),
+            y: self.mul_mod(&point.y, &z_inv_3),
+        })
+    }
+
+    fn double(&self, point: &JacobianPoint) -> JacobianPoint {
+        if point.is_infinity() || point.y.is_zero() {
+            return JacobianPoint::infinity();
+        }
+        let JacobianPoint { x, y, z } = point;
+
+        let y_2 = self.mul_mod(y, y);
+        let z_2 = self.mul_mod(z, z);
+        // S = 4 * X * Y^2
+        let s = self.mul_mod(&(x * 4_u8), &y_2);
+        // M = 3 * X^2 + a * Z^4
+        let m = self.add_mod(
+            &(self.mul_mod(x, x) * 3_u8),
+            &self.mul_mod(&self.a, &self.mul_mod(&z_2, &z_2)),
+        );
+        // X' = M^2 - 2 * S
+        let x3 = self.sub_mod(&self.mul_mod(&m, &m), &((&s * 2_u8) % &self.p));
+        // Y' = M * (S - X') - 8 * Y^4
+        let y3 = self.sub_mod(
+            &self.mul_mod(&m, &self.sub_mod(&s, &x3)),
+            &((self.mul_mod(&y_2, &y_2) * 8_u8) % &self.p),
+        );
+        // Z' = 2 * Y * Z
+        let z3 = self.mul_mod(&(y * 2_u8), z);
+
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    fn add(&self, p1: &JacobianPoint, p2: &JacobianPoint) -> JacobianPoint {
+        if p1.is_infinity() {
+            return p2.to_owned();
+        }
+        if p2.is_infinity() {
+            return p1.to_owned();
+        }
+
+        let z1_2 = self.mul_mod(&p1.z, &p1.z);
+        let z2_2 = self.mul_mod(&p2.z, &p2.z);
+        let u1 = self.mul_mod(&p1.x, &z2_2);
+        let u2 = self.mul_mod(&p2.x, &z1_2);
+        let s1 = self.mul_mod(&p1.y, &self.mul_mod(&z2_2, &p2.z));
+        let s2 = self.mul_mod(&p2.y, &self.mul_mod(&z1_2, &p1.z));
+
+        if u1 == u2 {
+            if s1 != s2 {
+                return JacobianPoint::infinity();
+            }
+            return self.double(p1);
+        }
+
+        let h = self.sub_mod(&u2, &u1);
+        let r = self.sub_mod(&s2, &s1);
+        let h_2 = self.mul_mod(&h, &h);
+        let h_3 = self.mul_mod(&h_2, &h);
+        let u1_h_2 = self.mul_mod(&u1, &h_2);
+
+        // X3 = R^2 - H^3 - 2 * U1 * H^2
+        let x3 = self.sub_mod(
+            &self.sub_mod(&self.mul_mod(&r, &r), &h_3),
+            &((&u1_h_2 * 2_u8) % &self.p),
+        );
+        // Y3 = R * (U1 * H^2 - X3) - S1 * H^3
+        let y3 = self.sub_mod(
+            &self.mul_mod(&r, &self.sub_mod(&u1_h_2, &x3)),
+            &self.mul_mod(&s1, &h_3),
+        );
+        // Z3 = H * Z1 * Z2
+        let z3 = self.mul_mod(&h, &self.mul_mod(&p1.z, &p2.z));
+
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    fn mul_jacobian(&self, k: &BigUint, point: &JacobianPoint) -> JacobianPoint {
+        let mut ret = JacobianPoint::infinity();
+        for i in (0..k.bits()).rev() {
+            ret = self.double(&ret);
+            if k.bit(i) {
+                ret = self.add(&ret, point);
+            }
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num_traits::Num as _;
+
+    use crate::{PRIVATE_KEY, PUBLIC_KEY_X, PUBLIC_KEY_Y};
+
+    #[test]
+    fn test_g_mul() {
+        let curve = Curve::new();
+        assert!(curve.is_on_curve(&curve.g));
+
+        let d = BigUint::from_str_radix(PRIVATE_KEY, 16).unwrap();
+        let point = curve.g_mul(&d).unwrap();
+        assert_eq!(point.x, BigUint::from_str_radix(PUBLIC_KEY_X, 16).unwrap());
+        assert_eq!(point.y, BigUint::from_str_radix(PUBLIC_KEY_Y, 16).unwrap());
+        assert!(curve.is_on_curve(&point));
+
+        assert_eq!(curve.g_mul(curve.n()), None);
+        assert_eq!(curve.g_mul(&BigUint::zero()), None);
+        assert_eq!(curve.g_mul(&(curve.n() + 1_u8)).unwrap(), curve.g);
+    }
+
+    #[test]
+    fn test_g_mul_add() {
+        let curve = Curve::new();
+
+        let k1 = BigUint::from(7_u8);
+        let k2 = BigUint::from(11_u8);
+        let point = curve.g_mul(&BigUint::from(3_u8)).unwrap();
+        assert_eq!(
+            curve.g_mul_add(&k1, &k2, &point),
+            curve.g_mul(&BigUint::from(7_u8 + 11 * 3))
+        );
+        assert_eq!(curve.g_mul_add(&k1, &(curve.n() - 7_u8), &curve.g), None);
+    }
+}
@@ -0,0 +1,278 @@
+//! Variable-time arithmetic for verification, where every scalar is public.
+//!
+//! [s]G + [t]P is computed in a single pass over the interleaved width-w NAFs of s and t
+//! (Shamir's trick), so the doublings are shared. The odd multiples of G are built once and
+//! kept in `context::g_table`.
+
+use subtle::ConstantTimeEq as _;
+
+use super::arith::{from_be_bytes, FieldElement, ProjectivePoint, Scalar};
+
+/// Odd multiples of G, for 7-bit NAF windows.
+pub(crate) type GTable = OddMultiples<32>;
+/// Odd multiples of a public key, for 5-bit NAF windows. Cheap enough to build per call.
+pub(crate) type PTable = OddMultiples<8>;
+/// Odd multiples of a prepared public key, as wide as those of G.
+pub(crate) type KeyTable = OddMultiples<32>;
+
+/// P, 3P, 5P, ..., (2 * LEN - 1)P, for width-w NAF digits with LEN = 2^(w - 2).
+#[derive(Debug, Clone)]
+pub(crate) struct OddMultiples<const LEN: usize>([ProjectivePoint; LEN]);
+
+impl<const LEN: usize> OddMultiples<LEN> {
+    const WIDTH: u32 = LEN.trailing_zeros() + 2;
+
+    pub(crate) fn new(point: &ProjectivePoint) -> Self {
+        let double = point.double();
+        let mut table = [*point; LEN];
+        for i in 1..LEN {
+            table[i] = table[i - 1].add(&double);
+        }
+        Self(table)
+    }
+
+    /// acc + [digit]P for an odd digit of the NAF.
+    fn add_digit(&self, acc: &ProjectivePoint, digit: i8) -> ProjectivePoint {
+        let point = &self.0[usize::from(digit.unsigned_abs() / 2)];
+        if digit > 0 {
+            acc.add(point)
+        } else {
+            acc.add(&-*point)
+        }
+    }
+}
+
+/// Width-w NAF of k, least significant digit first. Nonzero digits are odd, below 2^(w - 1)
+/// in absolute value and followed by at least w - 1 zeros.
+fn wnaf(k: &[u8; 32], width: u32) -> [i8; 257] {
+    let limbs = from_be_bytes(k);
+    // One more limb for the carry of a negative digit
+    let mut k = [limbs[0], limbs[1], limbs[2], limbs[3], 0];
+    let mut naf = [0; 257];
+
+    let mut i = 0;
+    while k != [0; 5] {
+        if k[0] & 1 == 1 {
+            let window = (k[0] & ((1 << width) - 1)) as i64;
+            let digit = if window >= 1 << (width - 1) {
+                window - (1 << width)
+            } else {
+                window
+            };
+            naf[i] = digit as i8;
+
+            // k -= digit, which clears the low w bits
+            if digit > 0 {
+                k[0] -= digit as u64;
+            } else {
+                let mut carry = digit.unsigned_abs();
+                for limb in k.iter_mut() {
+                    let (sum, overflow) = limb.overflowing_add(carry);
+                    *limb = sum;
+                    carry = overflow as u64;
+                }
+            }
+        }
+
+        for j in 0..4 {
+            k[j] = (k[j] >> 1) | (k[j + 1] << 63);
+        }
+        k[4] >>= 1;
+        i += 1;
+    }
+    naf
+}
+
+/// [a]A + [b]B with the odd multiples of A and B.
+pub(crate) fn mul_add<const A: usize, const B: usize>(
+    a_table: &OddMultiples<A>,
+    a: &[u8; 32],
+    b_table: &OddMultiples<B>,
+    b: &[u8; 32],
+) -> ProjectivePoint {
+    let a_naf = wnaf(a, OddMultiples::<A>::WIDTH);
+    let b_naf = wnaf(b, OddMultiples::<B>::WIDTH);
+
+    let mut acc = ProjectivePoint::IDENTITY;
+    let Some(top) = (0..257).rev().find(|&i| a_naf[i] != 0 || b_naf[i] != 0) else {
+        return acc;
+    };
+    for i in (0..=top).rev() {
+        acc = acc.double();
+        if a_naf[i] != 0 {
+            acc = a_table.add_digit(&acc, a_naf[i]);
+        }
+        if b_naf[i] != 0 {
+            acc = b_table.add_digit(&acc, b_naf[i]);
+        }
+    }
+    acc
+}
+
+/// Checks r || s against the digest e, see GB/T 32918.2-2016 7.1. r and s must already be in
+/// [1, n - 1] and the table built from a valid public key.
+pub(crate) fn verify<const LEN: usize>(
+    g_table: &GTable,
+    p_table: &OddMultiples<LEN>,
+    e: &[u8; 32],
+    signature: &[u8; 64],
+) -> bool {
+    let r = Scalar::from_bytes(crate::types::first_half(signature));
+    let s_bytes = crate::types::second_half(signature);
+
+    // t = (r + s) mod n
+    let t = r + Scalar::from_bytes(s_bytes);
+    if bool::from(t.is_zero()) {
+        return false;
+    }
+
+    // (x_1, y_1) = [s]G + [t]P
+    let point = mul_add(g_table, s_bytes, p_table, &t.to_bytes());
+    if bool::from(point.z.is_zero()) {
+        return false;
+    }
+    let x_1 = Scalar::from_bytes(crate::types::first_half(&point.to_affine()));
+
+    // R = (e + x_1) mod n
+    bool::from((Scalar::from_bytes(e) + x_1).ct_eq(&r))
+}
+
+/// Whether x || y is an affine point on the curve, with both coordinates below p.
+pub(crate) fn is_on_curve(point: &[u8; 64]) -> bool {
+    let x = crate::types::first_half(point);
+    let y = crate::types::second_half(point);
+    let (x_elem, y_elem) = (FieldElement::from_bytes(x), FieldElement::from_bytes(y));
+    // from_bytes reduces, so only canonical coordinates round-trip
+    if x_elem.to_bytes() != *x || y_elem.to_bytes() != *y {
+        return false;
+    }
+
+    // y^2 = x^3 + a * x + b with a = -3
+    let x_3 = x_elem.square() * x_elem;
+    bool::from(
+        y_elem
+            .square()
+            .ct_eq(&(x_3 - (x_elem.double() + x_elem) + super::arith::B)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num_bigint::BigUint;
+    use rand::{rngs::StdRng, RngCore as _, SeedableRng as _};
+
+    use crate::backend::soft::{AffinePoint, Curve};
+
+    #[test]
+    fn test_wnaf() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut scalars = vec![[0; 32], [0xFF; 32], crate::curve::N_MINUS_1];
+        scalars.extend((0..50).map(|_| {
+            let mut k = [0; 32];
+            rng.fill_bytes(&mut k);
+            k
+        }));
+
+        for k in scalars {
+            for width in [5, 7] {
+                let naf = wnaf(&k, width);
+                // sum of digit * 2^i, with the negative digits subtracted
+                let (mut pos, mut neg) = (BigUint::default(), BigUint::default());
+                for (i, digit) in naf.iter().enumerate() {
+                    let term = BigUint::from(digit.unsigned_abs()) << i;
+                    if *digit > 0 {
+                        pos += term;
+                    } else {
+                        neg += term;
+                    }
+                }
+                assert_eq!(pos - neg, BigUint::from_bytes_be(&k));
+
+                for (i, digit) in naf.iter().enumerate().filter(|(_, d)| **d != 0) {
+                    assert_eq!(digit % 2, 1 - 2 * i8::from(*digit < 0));
+                    assert!(digit.unsigned_abs() < 1 << (width - 1));
+                    let end = (i + width as usize).min(naf.len());
+                    assert!(naf[i + 1..end].iter().all(|d| *d == 0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_add_matches_soft() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let curve = Curve::new();
+        let n = curve.n();
+        let g_table = GTable::new(&ProjectivePoint::GENERATOR);
+
+        let random =
+            |rng: &mut StdRng| crate::types::to_bytes::<32>(&crate::curve::random_scalar(rng, n));
+        let point = curve
+            .g_mul(&BigUint::from_bytes_be(&random(&mut rng)))
+            .unwrap();
+        let p_table = PTable::new(&ProjectivePoint::from_affine(&point.to_array()));
+
+        let mut one = [0; 32];
+        one[31] = 1;
+        let mut pairs = vec![
+            (one, one),
+            (crate::curve::N_MINUS_1, one),
+            (one, crate::curve::N_MINUS_1),
+        ];
+        pairs.extend((0..20).map(|_| (random(&mut rng), random(&mut rng))));
+
+        for (a, b) in pairs {
+            let expected = curve
+                .g_mul_add(
+                    &BigUint::from_bytes_be(&a),
+                    &BigUint::from_bytes_be(&b),
+                    &point,
+                )
+                .map(|point| point.to_array())
+                .unwrap_or([0; 64]);
+            assert_eq!(mul_add(&g_table, &a, &p_table, &b).to_affine(), expected);
+        }
+
+        // [n - 1]G + [1]G is the identity
+        let g = PTable::new(&ProjectivePoint::GENERATOR);
+        assert_eq!(
+            mul_add(&g_table, &crate::curve::N_MINUS_1, &g, &one).to_affine(),
+            [0; 64]
+        );
+        assert_eq!(
+            mul_add(&g_table, &[0; 32], &g, &[0; 32]).to_affine(),
+            [0; 64]
+        );
+    }
+
+    #[test]
+    fn test_is_on_curve() {
+        let curve = Curve::new();
+        let mut rng = StdRng::seed_from_u64(2);
+
+        let mut g = [0; 64];
+        g[..32].copy_from_slice(&crate::curve::GX);
+        g[32..].copy_from_slice(&crate::curve::GY);
+        let mut off_curve = g;
+        off_curve[63] ^= 1;
+        let mut unreduced = g;
+        unreduced[..32].copy_from_slice(&crate::curve::P);
+
+        let mut points = vec![g, off_curve, unreduced, [0; 64], [0xFF; 64]];
+        points.extend((0..20).map(|_| {
+            let mut point = [0; 64];
+            rng.fill_bytes(&mut point);
+            point
+        }));
+        for point in points {
+            let affine = AffinePoint {
+                x: BigUint::from_bytes_be(&point[..32]),
+                y: BigUint::from_bytes_be(&point[32..]),
+            };
+            assert_eq!(is_on_curve(&point), curve.is_on_curve(&affine));
+        }
+        assert!(is_on_curve(&g));
+    }
+}
@@ -0,0 +1,70 @@
+//! Curve contexts shared by every call, built on first use.
+//!
+//! Without `std` the soft curve and the table of G are kept in a `spin::Once` instead of a
+//! `OnceLock`.
+
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+use crate::backend::{arith::ProjectivePoint, soft::Curve, vartime::GTable};
+
+/// The part of `OnceLock` used here.
+#[cfg(not(feature = "std"))]
+struct OnceLock<T>(spin::Once<T>);
+
+#[cfg(not(feature = "std"))]
+impl<T> OnceLock<T> {
+    const fn new() -> Self {
+        Self(spin::Once::new())
+    }
+
+    fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        self.0.call_once(f)
+    }
+}
+
+pub(crate) fn curve() -> &'static Curve {
+    static CURVE: OnceLock<Curve> = OnceLock::new();
+    CURVE.get_or_init(Curve::new)
+}
+
+pub(crate) fn g_table() -> &'static GTable {
+    static G_TABLE: OnceLock<GTable> = OnceLock::new();
+    G_TABLE.get_or_init(|| GTable::new(&ProjectivePoint::GENERATOR))
+}
+
+#[cfg(all(feature = "std", not(feature = "constant-time")))]
+pub(crate) fn sig_ctx() -> &'static libsm::sm2::signature::SigCtx {
+    static SIG_CTX: OnceLock<libsm::sm2::signature::SigCtx> = OnceLock::new();
+    SIG_CTX.get_or_init(libsm::sm2::signature::SigCtx::new)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn ecc_ctx() -> &'static libsm::sm2::ecc::EccCtx {
+    static ECC_CTX: OnceLock<libsm::sm2::ecc::EccCtx> = OnceLock::new();
+    ECC_CTX.get_or_init(libsm::sm2::ecc::EccCtx::new)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn sm2_p256() -> &'static gmsm::g2::p256::Sm2P256Curve {
+    static SM2_P256: OnceLock<gmsm::g2::p256::Sm2P256Curve> = OnceLock::new();
+    SM2_P256.get_or_init(gmsm::g2::p256::Sm2P256Curve::new)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_across_threads() {
+        let addrs = (0..4)
+            .map(|_| std::thread::spawn(|| sm2_p256() as *const _ as usize))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        assert!(addrs.iter().all(|addr| *addr == addrs[0]));
+        assert!(core::ptr::eq(curve(), curve()));
+        assert!(core::ptr::eq(g_table(), g_table()));
+    }
+}
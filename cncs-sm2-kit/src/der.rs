@@ -0,0 +1,198 @@
+//! Minimal DER reader / writer, covering the ASN.1 structures of GB/T 35276-2017 and the
+//! PKCS#8 / SEC1 / SubjectPublicKeyInfo key formats.
+
+use alloc::vec::Vec;
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OBJECT_IDENTIFIER: u8 = 0x06;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+/// Constructed, context-specific [0] and [1].
+pub(crate) const TAG_CONTEXT_0: u8 = 0xA0;
+pub(crate) const TAG_CONTEXT_1: u8 = 0xA1;
+
+/// id-ecPublicKey, 1.2.840.10045.2.1
+pub(crate) const OID_EC_PUBLIC_KEY: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+/// sm2p256v1, 1.2.156.10197.1.301
+pub(crate) const OID_SM2: &[u8] = &[0x2A, 0x81, 0x1C, 0xCF, 0x55, 0x01, 0x82, 0x2D];
+
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the content of the next TLV, which must have the given tag.
+    pub(crate) fn read(&mut self, tag: u8) -> Option<&'a [u8]> {
+        let (&actual_tag, rest) = self.bytes.split_first()?;
+        if actual_tag != tag {
+            return None;
+        }
+
+        let (&first, rest) = rest.split_first()?;
+        let (len, rest) = if first < 0x80 {
+            (first as usize, rest)
+        } else {
+            let num_bytes = (first & 0x7F) as usize;
+            if num_bytes == 0 || num_bytes > core::mem::size_of::<usize>() || rest.len() < num_bytes
+            {
+                return None;
+            }
+            let (len_bytes, rest) = rest.split_at(num_bytes);
+            // Long form must be minimal
+            if len_bytes[0] == 0 {
+                return None;
+            }
+            let len = len_bytes
+                .iter()
+                .fold(0_usize, |len, b| (len << 8) | *b as usize);
+            if len < 0x80 {
+                return None;
+            }
+            (len, rest)
+        };
+
+        if rest.len() < len {
+            return None;
+        }
+        let (content, rest) = rest.split_at(len);
+        self.bytes = rest;
+        Some(content)
+    }
+
+    /// Returns the big-endian magnitude of the next non-negative INTEGER.
+    pub(crate) fn read_unsigned_integer(&mut self) -> Option<&'a [u8]> {
+        let content = self.read(TAG_INTEGER)?;
+        match content {
+            [] => None,
+            [first, ..] if *first >= 0x80 => None,
+            [0, second, ..] if *second < 0x80 => None,
+            [0, rest @ ..] => Some(rest),
+            _ => Some(content),
+        }
+    }
+}
+
+/// Returns the content of `bytes` when it is exactly one TLV with the given tag.
+pub(crate) fn read_only(bytes: &[u8], tag: u8) -> Option<&[u8]> {
+    let mut reader = Reader::new(bytes);
+    let content = reader.read(tag)?;
+    reader.is_empty().then_some(content)
+}
+
+/// Reads the AlgorithmIdentifier of an SM2 key, SEQUENCE { id-ecPublicKey, sm2p256v1 }.
+pub(crate) fn read_sm2_algorithm(reader: &mut Reader) -> Option<()> {
+    let mut algorithm = Reader::new(reader.read(TAG_SEQUENCE)?);
+    let ok = algorithm.read(TAG_OBJECT_IDENTIFIER)? == OID_EC_PUBLIC_KEY
+        && algorithm.read(TAG_OBJECT_IDENTIFIER)? == OID_SM2
+        && algorithm.is_empty();
+    ok.then_some(())
+}
+
+pub(crate) fn write_sm2_algorithm(out: &mut Vec<u8>) {
+    let mut algorithm = Vec::new();
+    write(&mut algorithm, TAG_OBJECT_IDENTIFIER, OID_EC_PUBLIC_KEY);
+    write(&mut algorithm, TAG_OBJECT_IDENTIFIER, OID_SM2);
+    write(out, TAG_SEQUENCE, &algorithm);
+}
+
+pub(crate) fn write(out: &mut Vec<u8>, tag: u8, content: &[u8]) {
+    out.push(tag);
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let len_bytes = len.to_be_bytes();
+        let skip = len_bytes.iter().take_while(|b| **b == 0).count();
+        out.push(0x80 | (len_bytes.len() - skip) as u8);
+        out.extend_from_slice(&len_bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+}
+
+/// Writes a big-endian magnitude as a non-negative INTEGER.
+pub(crate) fn write_unsigned_integer(out: &mut Vec<u8>, bytes: &[u8]) {
+    let skip = bytes.iter().take_while(|b| **b == 0).count();
+    let bytes = &bytes[skip..];
+    match bytes.first() {
+        None => write(out, TAG_INTEGER, &[0]),
+        Some(first) if *first >= 0x80 => {
+            let mut content = Vec::with_capacity(bytes.len() + 1);
+            content.push(0);
+            content.extend_from_slice(bytes);
+            write(out, TAG_INTEGER, &content)
+        }
+        Some(_) => write(out, TAG_INTEGER, bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer() {
+        for (bytes, der) in [
+            (&[][..], &[0x02, 0x01, 0x00][..]),
+            (&[0x00, 0x00], &[0x02, 0x01, 0x00]),
+            (&[0x00, 0x7F], &[0x02, 0x01, 0x7F]),
+            (&[0x80], &[0x02, 0x02, 0x00, 0x80]),
+            (&[0x01, 0x00], &[0x02, 0x02, 0x01, 0x00]),
+        ] {
+            let mut out = Vec::new();
+            write_unsigned_integer(&mut out, bytes);
+            assert_eq!(out, der);
+
+            let mut reader = Reader::new(der);
+            let magnitude = reader.read_unsigned_integer().unwrap();
+            assert!(reader.is_empty());
+            assert_eq!(
+                magnitude
+                    .iter()
+                    .skip_while(|b| **b == 0)
+                    .collect::<Vec<_>>(),
+                bytes.iter().skip_while(|b| **b == 0).collect::<Vec<_>>()
+            );
+        }
+
+        for der in [
+            &[0x02, 0x00][..],
+            &[0x02, 0x01, 0x80],
+            &[0x02, 0x02, 0x00, 0x7F],
+            &[0x04, 0x01, 0x00],
+            &[0x02, 0x02, 0x00],
+        ] {
+            assert!(Reader::new(der).read_unsigned_integer().is_none());
+        }
+    }
+
+    #[test]
+    fn test_length() {
+        for len in [0, 0x7F, 0x80, 0xFF, 0x100, 0x10000] {
+            let content = alloc::vec![0xAB; len];
+            let mut out = Vec::new();
+            write(&mut out, TAG_OCTET_STRING, &content);
+
+            let mut reader = Reader::new(&out);
+            assert_eq!(reader.read(TAG_OCTET_STRING).unwrap(), &content[..]);
+            assert!(reader.is_empty());
+        }
+
+        for der in [
+            &[0x04, 0x81, 0x7F][..],
+            &[0x04, 0x82, 0x00, 0x80],
+            &[0x04, 0x80],
+            &[0x04, 0x02, 0x00],
+        ] {
+            assert!(Reader::new(der).read(TAG_OCTET_STRING).is_none());
+        }
+    }
+}
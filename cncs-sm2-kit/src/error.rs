@@ -0,0 +1,114 @@
+use core::fmt;
+
+use num_bigint::ParseBigIntError;
+
+#[derive(Debug)]
+pub enum Error {
+    ParseHex(ParseBigIntError),
+    InvalidLength(usize),
+    InvalidPrefix,
+    Malformed,
+    InvalidPrivateKey,
+    InvalidPublicKey,
+    InvalidSignature,
+    InvalidUserId,
+    InvalidCiphertext,
+    BufferTooSmall(usize),
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ParseHex(err) => write!(f, "invalid hex string: {}", err),
+            Error::InvalidLength(len) => write!(f, "invalid length: {}", len),
+            Error::InvalidPrefix => write!(f, "invalid point prefix, expected 04"),
+            Error::Malformed => write!(f, "malformed DER or PEM"),
+            Error::InvalidPrivateKey => write!(f, "private key is out of range [1, n-2]"),
+            Error::InvalidPublicKey => write!(f, "public key is not a point on the SM2 curve"),
+            Error::InvalidSignature => write!(f, "signature r or s is out of range [1, n-1]"),
+            Error::InvalidUserId => write!(f, "invalid user id"),
+            Error::InvalidCiphertext => write!(f, "invalid ciphertext"),
+            Error::BufferTooSmall(len) => write!(f, "buffer too small, {} bytes needed", len),
+            #[cfg(feature = "std")]
+            Error::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ParseHex(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+//
+//
+//
+/// Why `verify_strict` rejected a signature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum VerificationError {
+    MalformedSignature,
+    SignatureOutOfRange,
+    InvalidPublicKey,
+    InvalidUserId,
+    Mismatch,
+}
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::MalformedSignature => {
+                write!(f, "signature is not 64 bytes of r || s")
+            }
+            VerificationError::SignatureOutOfRange => {
+                write!(f, "signature r or s is out of range [1, n-1]")
+            }
+            VerificationError::InvalidPublicKey => {
+                write!(f, "public key is not a point on the SM2 curve")
+            }
+            VerificationError::InvalidUserId => write!(f, "user id is longer than 8191 bytes"),
+            VerificationError::Mismatch => write!(f, "signature does not match"),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for VerificationError {}
+
+impl From<ParseBigIntError> for Error {
+    fn from(err: ParseBigIntError) -> Self {
+        Self::ParseHex(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::error::Error as _;
+
+    use crate::types::PublicKey;
+
+    #[test]
+    fn test_display_and_source() {
+        let err = PublicKey::from_hex_str("XYZ", "00").unwrap_err();
+        assert!(matches!(err, Error::ParseHex(_)));
+        assert!(err.to_string().starts_with("invalid hex string: "));
+        assert!(err.source().is_some());
+
+        let err = PublicKey::from_concated_hex_str("04").unwrap_err();
+        assert!(matches!(err, Error::InvalidLength(2)));
+        assert_eq!(err.to_string(), "invalid length: 2");
+        assert!(err.source().is_none());
+    }
+}
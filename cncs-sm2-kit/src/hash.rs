@@ -0,0 +1,153 @@
+use sm3::{Digest as _, Sm3};
+
+use crate::curve;
+
+pub(crate) const DEFAULT_USER_ID: &[u8] = b"1234567812345678";
+
+/// Z_A = SM3(ENTL_A || ID_A || a || b || x_G || y_G || x_A || y_A)
+pub(crate) fn z_a(user_id: &[u8], public_key: &[u8; 64]) -> [u8; 32] {
+    let mut hasher = Sm3::new();
+    hasher.update(((user_id.len() * 8) as u16).to_be_bytes());
+    hasher.update(user_id);
+    hasher.update(curve::A);
+    hasher.update(curve::B);
+    hasher.update(curve::GX);
+    hasher.update(curve::GY);
+    hasher.update(public_key);
+    hasher.finalize().into()
+}
+
+/// e = SM3(Z_A || M)
+pub(crate) fn e(user_id: &[u8], public_key: &[u8; 64], msg: &[u8]) -> [u8; 32] {
+    e_with_z_a(&z_a(user_id, public_key), msg)
+}
+
+pub(crate) fn e_with_z_a(z_a: &[u8; 32], msg: &[u8]) -> [u8; 32] {
+    let mut hasher = Sm3::new();
+    hasher.update(z_a);
+    hasher.update(msg);
+    hasher.finalize().into()
+}
+
+/// C3 = SM3(x2 || M || y2)
+pub(crate) fn c3(point: &[u8; 64], msg: &[u8]) -> [u8; 32] {
+    let mut hasher = Sm3::new();
+    hasher.update(&point[..32]);
+    hasher.update(msg);
+    hasher.update(&point[32..]);
+    hasher.finalize().into()
+}
+
+/// Fills `out` with KDF(x2 || y2, klen), see GB/T 32918.4-2016 5.4.3
+pub(crate) fn kdf(point: &[u8; 64], out: &mut [u8]) {
+    for (i, chunk) in out.chunks_mut(32).enumerate() {
+        let mut hasher = Sm3::new();
+        hasher.update(point);
+        hasher.update((i as u32 + 1).to_be_bytes());
+        let hash = hasher.finalize();
+        chunk.copy_from_slice(&hash[..chunk.len()]);
+    }
+}
+
+/// Whether KDF(x2 || y2, klen) is all zero, without materializing it.
+pub(crate) fn kdf_is_zero(point: &[u8; 64], len: u64) -> bool {
+    let mut kdf = Kdf::new(point);
+    let mut block = [0; 32];
+    let mut remaining = len;
+    while remaining > 0 {
+        let chunk = &mut block[..remaining.min(32) as usize];
+        chunk.fill(0);
+        kdf.apply(chunk);
+        if !kdf.is_zero() {
+            return false;
+        }
+        remaining -= chunk.len() as u64;
+    }
+    true
+}
+
+/// KDF(x2 || y2, klen) applied in pieces, for messages that are not held in memory.
+pub(crate) struct Kdf<'a> {
+    point: &'a [u8; 64],
+    counter: u32,
+    block: [u8; 32],
+    used: usize,
+    nonzero: bool,
+}
+impl<'a> Kdf<'a> {
+    pub(crate) fn new(point: &'a [u8; 64]) -> Self {
+        Self {
+            point,
+            counter: 0,
+            block: [0; 32],
+            used: 32,
+            nonzero: false,
+        }
+    }
+
+    /// XORs the next `data.len()` bytes of the key stream into `data`.
+    pub(crate) fn apply(&mut self, mut data: &mut [u8]) {
+        while !data.is_empty() {
+            if self.used == 32 {
+                self.counter += 1;
+                let mut hasher = Sm3::new();
+                hasher.update(self.point);
+                hasher.update(self.counter.to_be_bytes());
+                self.block = hasher.finalize().into();
+                self.used = 0;
+            }
+
+            let len = data.len().min(32 - self.used);
+            let (head, rest) = core::mem::take(&mut data).split_at_mut(len);
+            for (b, k) in head.iter_mut().zip(&self.block[self.used..]) {
+                self.nonzero |= *k != 0;
+                *b ^= k;
+            }
+            self.used += len;
+            data = rest;
+        }
+    }
+
+    /// Whether every key stream byte so far was zero.
+    pub(crate) fn is_zero(&self) -> bool {
+        !self.nonzero
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use crate::{types::PublicKey, PUBLIC_KEY_X, PUBLIC_KEY_Y};
+
+    #[test]
+    fn test_e_matches_libsm() {
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+        let pk = libsm::sm2::ecc::Point::try_from(&public_key).unwrap();
+        let sig_ctx = libsm::sm2::signature::SigCtx::new();
+
+        for user_id in ["1234567812345678", "", "ID12341234567890"] {
+            assert_eq!(
+                e(user_id.as_bytes(), &public_key.to_concated_array(), b"TEST"),
+                sig_ctx.hash(user_id, &pk, b"TEST")
+            );
+        }
+    }
+
+    #[test]
+    fn test_kdf_in_pieces() {
+        let point = [0x5A; 64];
+        let mut expected = [0; 100];
+        kdf(&point, &mut expected);
+
+        for piece_len in [1, 7, 32, 33, 100] {
+            let mut kdf = Kdf::new(&point);
+            let mut out = [0; 100];
+            out.chunks_mut(piece_len).for_each(|chunk| kdf.apply(chunk));
+            assert_eq!(out, expected);
+            assert!(!kdf.is_zero());
+        }
+        assert!(!kdf_is_zero(&point, 100));
+        assert!(kdf_is_zero(&point, 0));
+    }
+}
@@ -1,120 +1,533 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub use gmsm;
+#[cfg(feature = "std")]
 pub use libsm;
+pub use rand_core;
 
-use core::fmt;
+use alloc::{vec, vec::Vec};
 
-use gmsm::g2::subject::{
-    decrypt as gmsm_decrypt, encrypt as gmsm_encrypt, PrivateKey as GmsmPrivateKey,
-    PublicKey as GmsmPublicKey,
-};
-use libsm::sm2::{
-    ecc::Point as LibsmPoint,
-    signature::{SigCtx as LibsmSigCtx, Signature as LibsmSignature},
-};
+use num_bigint::BigUint;
+use num_traits::Zero as _;
+use rand_core::{CryptoRng, RngCore};
+use subtle::ConstantTimeEq as _;
 
+pub(crate) mod backend;
+pub(crate) mod context;
+pub(crate) mod curve;
+pub(crate) mod der;
+pub mod error;
+pub(crate) mod hash;
+pub(crate) mod pem;
+#[cfg(feature = "std")]
+pub mod stream;
 pub mod types;
 
-use self::types::{EncryptMode, PrivateKey, PublicKey, Signature};
+pub use self::error::{Error, VerificationError};
+use self::{
+    backend::soft::{AffinePoint, Curve},
+    types::{
+        Ciphertext, CiphertextLayout, DecodeOptions, EncryptMode, PrivateKey, PublicKey, Signature,
+    },
+};
+
+// ENTL_A holds the bit length of the user id in two bytes, see GB/T 32918.2-2016
+const USER_ID_MAX_LEN: usize = u16::MAX as usize / 8;
 
 //
 //
 //
+#[cfg(all(feature = "std", not(feature = "constant-time")))]
 pub fn sign<'a>(
     private_key: &PrivateKey,
     msg: impl AsRef<[u8]>,
-    user_id: impl Into<Option<&'a str>>,
-) -> Signature {
+    user_id: impl Into<Option<&'a [u8]>>,
+) -> Result<Signature, Error> {
     let msg = msg.as_ref();
     let user_id = user_id.into();
 
-    let sk = &private_key.d;
+    private_key.validate()?;
+    check_user_id(user_id)?;
 
-    let pk = LibsmPoint::from(private_key);
+    // libsm only hashes str user ids
+    let e = hash::e(
+        user_id.unwrap_or(hash::DEFAULT_USER_ID),
+        &private_key.public_key().to_concated_array(),
+        msg,
+    );
+    let signature = context::sig_ctx().sign_raw(&e, &private_key.to_biguint());
 
-    let sig_ctx = LibsmSigCtx::new();
-    let signature = if let Some(user_id) = user_id {
-        let e_bytes = sig_ctx.hash(user_id, &pk, msg);
-        sig_ctx.sign_raw(&e_bytes[..], sk)
-    } else {
-        sig_ctx.sign(msg, sk, &pk)
-    };
+    Signature::try_from(&signature)
+}
+
+/// With `constant-time`, `sign_with_rng` on the OS RNG.
+#[cfg(all(feature = "std", feature = "constant-time"))]
+pub fn sign<'a>(
+    private_key: &PrivateKey,
+    msg: impl AsRef<[u8]>,
+    user_id: impl Into<Option<&'a [u8]>>,
+) -> Result<Signature, Error> {
+    sign_with_rng(&mut rand_core::OsRng, private_key, msg, user_id)
+}
+
+/// Like `sign`, but writes r || s to `out`.
+///
+/// This only saves the `Signature`. Without `constant-time` libsm still allocates for its
+/// `BigUint` arithmetic on every call.
+#[cfg(feature = "std")]
+pub fn sign_into<'a>(
+    private_key: &PrivateKey,
+    msg: impl AsRef<[u8]>,
+    user_id: impl Into<Option<&'a [u8]>>,
+    out: &mut [u8; 64],
+) -> Result<(), Error> {
+    *out = sign(private_key, msg, user_id)?.to_concated_array();
+    Ok(())
+}
+
+pub fn sign_with_rng<'a>(
+    rng: &mut (impl CryptoRng + RngCore),
+    private_key: &PrivateKey,
+    msg: impl AsRef<[u8]>,
+    user_id: impl Into<Option<&'a [u8]>>,
+) -> Result<Signature, Error> {
+    let mut bytes = [0; 64];
+    sign_with_rng_into(rng, private_key, msg, user_id, &mut bytes)?;
+    Signature::from_concated_bytes(&bytes)
+}
+
+/// Like `sign_with_rng`, but writes r || s to `out`.
+///
+/// Allocation-free only with `constant-time`, otherwise `PrivateKey::public_key` allocates
+/// for Z_A.
+pub fn sign_with_rng_into<'a>(
+    rng: &mut (impl CryptoRng + RngCore),
+    private_key: &PrivateKey,
+    msg: impl AsRef<[u8]>,
+    user_id: impl Into<Option<&'a [u8]>>,
+    out: &mut [u8; 64],
+) -> Result<(), Error> {
+    let msg = msg.as_ref();
+    let user_id = user_id.into();
+
+    private_key.validate()?;
+    check_user_id(user_id)?;
 
-    Signature::from(&signature)
+    let e = hash::e(
+        user_id.unwrap_or(hash::DEFAULT_USER_ID),
+        &private_key.public_key().to_concated_array(),
+        msg,
+    );
+    *out = sign_digest(rng, private_key, &e);
+    Ok(())
+}
+
+/// r || s for the digest e, see GB/T 32918.2-2016 6.1.
+///
+/// k is secret, so [k]G always runs on the constant-time backend, whatever the features.
+fn sign_digest(
+    rng: &mut (impl CryptoRng + RngCore),
+    private_key: &PrivateKey,
+    e: &[u8; 32],
+) -> [u8; 64] {
+    backend::ct::sign(rng, private_key.as_array(), e)
 }
 
 //
 //
 //
+/// Computes [s]G + [t]P in one interleaved pass, see `VerifyingKey` to reuse the work done
+/// per public key.
 pub fn verify<'a>(
     public_key: &PublicKey,
     msg: impl AsRef<[u8]>,
-    user_id: impl Into<Option<&'a str>>,
+    user_id: impl Into<Option<&'a [u8]>>,
     signature: &Signature,
-) -> Result<bool, VerifyError> {
+) -> Result<bool, Error> {
     let msg = msg.as_ref();
     let user_id = user_id.into();
 
-    let pk = LibsmPoint::try_from(public_key).map_err(VerifyError::ToLibsmPointFailed)?;
+    check_user_id(user_id)?;
+    public_key.validate()?;
+    signature.validate()?;
 
-    let signature = LibsmSignature::from(signature);
+    let public_key = public_key.as_concated_array();
+    let e = hash::e(user_id.unwrap_or(hash::DEFAULT_USER_ID), public_key, msg);
+    let table =
+        backend::vartime::PTable::new(&backend::arith::ProjectivePoint::from_affine(public_key));
+    Ok(backend::vartime::verify(
+        context::g_table(),
+        &table,
+        &e,
+        signature.as_concated_array(),
+    ))
+}
 
-    let sig_ctx = LibsmSigCtx::new();
-    let ret = if let Some(user_id) = user_id {
-        let e_bytes = sig_ctx.hash(user_id, &pk, msg);
-        sig_ctx.verify_raw(&e_bytes[..], &pk, &signature)
-    } else {
-        sig_ctx.verify(msg, &pk, &signature)
+pub fn verify_via_soft<'a>(
+    public_key: &PublicKey,
+    msg: impl AsRef<[u8]>,
+    user_id: impl Into<Option<&'a [u8]>>,
+    signature: &Signature,
+) -> Result<bool, Error> {
+    let msg = msg.as_ref();
+    let user_id = user_id.into();
+
+    check_user_id(user_id)?;
+    signature.validate()?;
+
+    let curve = context::curve();
+    if !curve.is_on_curve(&AffinePoint::from(public_key)) {
+        return Err(Error::InvalidPublicKey);
+    }
+    let n = curve.n();
+    let r = &BigUint::from_bytes_be(signature.r());
+    let s = &BigUint::from_bytes_be(signature.s());
+
+    // t = (r + s) mod n
+    let t = (r + s) % n;
+    if t.is_zero() {
+        return Ok(false);
+    }
+
+    let x_1 = match curve.g_mul_add(s, &t, &AffinePoint::from(public_key)) {
+        Some(point) => point.x,
+        None => return Ok(false),
     };
 
-    Ok(ret)
+    let e = BigUint::from_bytes_be(&hash::e(
+        user_id.unwrap_or(hash::DEFAULT_USER_ID),
+        &public_key.to_concated_array(),
+        msg,
+    ));
+
+    // R = (e + x_1) mod n
+    Ok((e + x_1) % n == *r)
 }
-#[derive(Debug)]
-pub enum VerifyError {
-    ToLibsmPointFailed(String),
+
+/// Like `verify`, but takes the 64-byte r || s encoding and reports why verification failed.
+pub fn verify_strict<'a>(
+    public_key: &PublicKey,
+    msg: impl AsRef<[u8]>,
+    user_id: impl Into<Option<&'a [u8]>>,
+    signature: impl AsRef<[u8]>,
+) -> Result<(), VerificationError> {
+    let user_id = user_id.into();
+
+    check_user_id(user_id).map_err(|_| VerificationError::InvalidUserId)?;
+    public_key
+        .validate()
+        .map_err(|_| VerificationError::InvalidPublicKey)?;
+    let signature =
+        Signature::from_concated_bytes(signature.as_ref()).map_err(|err| match err {
+            Error::InvalidSignature => VerificationError::SignatureOutOfRange,
+            _ => VerificationError::MalformedSignature,
+        })?;
+
+    match verify(public_key, msg, user_id, &signature) {
+        Ok(true) => Ok(()),
+        _ => Err(VerificationError::Mismatch),
+    }
 }
-impl fmt::Display for VerifyError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+
+fn check_user_id(user_id: Option<&[u8]>) -> Result<(), Error> {
+    match user_id {
+        Some(user_id) if user_id.len() > USER_ID_MAX_LEN => Err(Error::InvalidUserId),
+        _ => Ok(()),
     }
 }
-impl std::error::Error for VerifyError {}
 
 //
 //
 //
+#[cfg(feature = "std")]
 pub fn encrypt(
     public_key: &PublicKey,
     msg: impl AsRef<[u8]>,
     mode: impl Into<Option<EncryptMode>>,
-) -> Vec<u8> {
+) -> Result<Vec<u8>, Error> {
+    let msg = msg.as_ref();
+
+    let mut ciphertext = vec![0; ciphertext_len(msg.len())];
+    encrypt_into(public_key, msg, mode, &mut ciphertext)?;
+    Ok(ciphertext)
+}
+
+pub fn encrypt_with_rng(
+    rng: &mut (impl CryptoRng + RngCore),
+    public_key: &PublicKey,
+    msg: impl AsRef<[u8]>,
+    mode: impl Into<Option<EncryptMode>>,
+) -> Result<Vec<u8>, Error> {
+    let msg = msg.as_ref();
+
+    let mut ciphertext = vec![0; ciphertext_len(msg.len())];
+    encrypt_with_rng_into(rng, public_key, msg, mode, &mut ciphertext)?;
+    Ok(ciphertext)
+}
+
+/// Like `encrypt`, but writes the ciphertext to the front of `out` and returns its length,
+/// see `ciphertext_len`.
+#[cfg(feature = "std")]
+pub fn encrypt_into(
+    public_key: &PublicKey,
+    msg: impl AsRef<[u8]>,
+    mode: impl Into<Option<EncryptMode>>,
+    out: &mut [u8],
+) -> Result<usize, Error> {
+    encrypt_with_rng_into(&mut rand_core::OsRng, public_key, msg, mode, out)
+}
+
+/// Like `encrypt_with_rng`, but writes the ciphertext to the front of `out` and returns its
+/// length, see `ciphertext_len`.
+pub fn encrypt_with_rng_into(
+    rng: &mut (impl CryptoRng + RngCore),
+    public_key: &PublicKey,
+    msg: impl AsRef<[u8]>,
+    mode: impl Into<Option<EncryptMode>>,
+    out: &mut [u8],
+) -> Result<usize, Error> {
     let msg = msg.as_ref();
     let mode: EncryptMode = mode.into().unwrap_or_default();
 
-    gmsm_encrypt(
-        GmsmPublicKey::from(public_key),
-        msg.to_vec(),
-        mode.to_gmsm_mode(),
-    )
+    let len = ciphertext_len(msg.len());
+    if out.len() < len {
+        return Err(Error::BufferTooSmall(len));
+    }
+    public_key.validate()?;
+
+    let (c_1, rest) = out[..len].split_at_mut(1 + 64);
+    let (c_2, c_3) = match mode {
+        EncryptMode::C1C2C3 => rest.split_at_mut(msg.len()),
+        EncryptMode::C1C3C2 => {
+            let (c_3, c_2) = rest.split_at_mut(32);
+            (c_2, c_3)
+        }
+    };
+
+    let (point_1, x2y2) = ephemeral_points(rng, public_key, msg.len() as u64);
+
+    hash::kdf(&x2y2, c_2);
+    c_2.iter_mut().zip(msg).for_each(|(c, m)| *c ^= m);
+
+    c_1[0] = 0x04;
+    c_1[1..].copy_from_slice(&point_1);
+    c_3.copy_from_slice(&hash::c3(&x2y2, msg));
+
+    Ok(len)
+}
+
+/// Picks k and returns C1 = [k]G and [k]P_B as x2 || y2, retrying while
+/// KDF(x2 || y2, klen) is all zero. `public_key` must be valid.
+///
+/// k is secret, so both run on the constant-time backend, whatever the features.
+pub(crate) fn ephemeral_points(
+    rng: &mut (impl CryptoRng + RngCore),
+    public_key: &PublicKey,
+    msg_len: u64,
+) -> ([u8; 64], [u8; 64]) {
+    loop {
+        let k = backend::ct::random_scalar(rng);
+        let x2y2 = backend::ct::mul(&k, public_key.as_concated_array());
+        if msg_len > 0 && hash::kdf_is_zero(&x2y2, msg_len) {
+            continue;
+        }
+
+        return (backend::ct::g_mul(&k), x2y2);
+    }
+}
+
+/// Length of the `04`-prefixed raw ciphertext of a `msg_len`-byte message.
+pub const fn ciphertext_len(msg_len: usize) -> usize {
+    1 + 64 + 32 + msg_len
+}
+
+#[cfg(feature = "std")]
+pub fn encrypt_with_options(
+    public_key: &PublicKey,
+    msg: impl AsRef<[u8]>,
+    options: &types::EncodeOptions,
+) -> Result<Vec<u8>, Error> {
+    let bytes = encrypt(public_key, msg, options.mode)?;
+    Ok(Ciphertext::from_bytes(&bytes, options.mode)?.encode(options))
 }
 
 //
 //
 //
+#[cfg(feature = "std")]
 pub fn decrypt(
     private_key: &PrivateKey,
     msg: impl AsRef<[u8]>,
     mode: impl Into<Option<EncryptMode>>,
-) -> Vec<u8> {
+) -> Result<Vec<u8>, Error> {
     let msg = msg.as_ref();
-    let mode: EncryptMode = mode.into().unwrap_or_default();
 
-    gmsm_decrypt(
-        GmsmPrivateKey::from(private_key),
-        msg.to_vec(),
-        mode.to_gmsm_mode(),
+    private_key.validate()?;
+    let (c_1, c_3, c_2) = Ciphertext::split(msg, mode)?;
+
+    let (x2y2, valid) = shared_point_std(private_key, &c_1);
+    match open(&x2y2, c_2, c_3) {
+        Some(plaintext) if valid => Ok(plaintext),
+        _ => Err(Error::InvalidCiphertext),
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub fn decrypt(
+    private_key: &PrivateKey,
+    msg: impl AsRef<[u8]>,
+    mode: impl Into<Option<EncryptMode>>,
+) -> Result<Vec<u8>, Error> {
+    decrypt_via_soft(private_key, msg, mode)
+}
+
+pub fn decrypt_via_soft(
+    private_key: &PrivateKey,
+    msg: impl AsRef<[u8]>,
+    mode: impl Into<Option<EncryptMode>>,
+) -> Result<Vec<u8>, Error> {
+    decrypt_ciphertext(private_key, &Ciphertext::from_bytes(msg.as_ref(), mode)?)
+}
+
+pub fn decrypt_ciphertext(
+    private_key: &PrivateKey,
+    ciphertext: &Ciphertext,
+) -> Result<Vec<u8>, Error> {
+    private_key.validate()?;
+
+    let (x2y2, valid) = shared_point(context::curve(), private_key, &ciphertext.c1);
+    match open(&x2y2, &ciphertext.c2, &ciphertext.c3) {
+        Some(plaintext) if valid => Ok(plaintext),
+        _ => Err(Error::InvalidCiphertext),
+    }
+}
+
+/// Tries ASN.1 DER, then C1C3C2 and C1C2C3 with and without the `04` prefix,
+/// accepting the first layout whose C3 verifies.
+pub fn decrypt_auto(
+    private_key: &PrivateKey,
+    msg: impl AsRef<[u8]>,
+) -> Result<(Vec<u8>, CiphertextLayout), Error> {
+    let msg = msg.as_ref();
+
+    private_key.validate()?;
+
+    let curve = context::curve();
+
+    if let Ok(ciphertext) = Ciphertext::from_der(msg) {
+        let (x2y2, valid) = shared_point(curve, private_key, &ciphertext.c1);
+        match open(&x2y2, &ciphertext.c2, &ciphertext.c3) {
+            Some(plaintext) if valid => return Ok((plaintext, CiphertextLayout::Asn1)),
+            _ => {}
+        }
+    }
+
+    for prefix in [true, false] {
+        let bytes = match (prefix, msg.split_first()) {
+            (true, Some((0x04, bytes))) => bytes,
+            (true, _) => continue,
+            (false, _) => msg,
+        };
+        if bytes.len() < 64 + 32 {
+            continue;
+        }
+
+        // C1 is the same for both orders
+        let c_1 = PublicKey::from_concated_bytes(&bytes[..64])?;
+        let (x2y2, valid) = shared_point(curve, private_key, &c_1);
+        for mode in [EncryptMode::C1C3C2, EncryptMode::C1C2C3] {
+            let ciphertext = Ciphertext::from_bytes_without_prefix(bytes, mode)?;
+            // Opened for an invalid C1 too, as in `decrypt`
+            match open(&x2y2, &ciphertext.c2, &ciphertext.c3) {
+                Some(plaintext) if valid => {
+                    return Ok((plaintext, CiphertextLayout::Raw { mode, prefix }))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Err(Error::InvalidCiphertext)
+}
+
+/// Returns C1 and whether it is valid. An invalid C1 is swapped for G, so that rejecting it
+/// costs the same scalar multiplication and C3 check as a C3 mismatch.
+///
+/// The cofactor is 1 and d is in [1, n-2], so [d]C1 is never infinity for a valid C1.
+fn c1_or_g(curve: &Curve, c_1: &PublicKey) -> (AffinePoint, bool) {
+    if c_1.validate().is_ok() {
+        (AffinePoint::from(c_1), true)
+    } else {
+        (curve.g().clone(), false)
+    }
+}
+
+/// [d]C1 as x2 || y2 and whether C1 is valid, see `c1_or_g`.
+///
+/// `Curve::mul` is a variable-time double-and-add over `BigUint`, so the timing depends on
+/// d. Enable `constant-time` to run it on the fixed-window backend instead.
+#[cfg(not(feature = "constant-time"))]
+fn shared_point(curve: &Curve, private_key: &PrivateKey, c_1: &PublicKey) -> ([u8; 64], bool) {
+    let (c_1, valid) = c1_or_g(curve, c_1);
+    match curve.mul(&private_key.to_biguint(), &c_1) {
+        Some(point) => (point.to_array(), valid),
+        None => ([0; 64], false),
+    }
+}
+
+/// [d]C1 as x2 || y2 and whether C1 is valid, see `c1_or_g`.
+#[cfg(feature = "constant-time")]
+fn shared_point(curve: &Curve, private_key: &PrivateKey, c_1: &PublicKey) -> ([u8; 64], bool) {
+    let (c_1, valid) = c1_or_g(curve, c_1);
+    (
+        backend::ct::mul(private_key.as_array(), &c_1.to_array()),
+        valid,
     )
 }
 
+/// Like `shared_point`, multiplying with gmsm unless `constant-time` is enabled. gmsm's
+/// scalar multiplication is not constant-time either.
+#[cfg(all(feature = "std", not(feature = "constant-time")))]
+pub(crate) fn shared_point_std(private_key: &PrivateKey, c_1: &PublicKey) -> ([u8; 64], bool) {
+    let (c_1, valid) = c1_or_g(context::curve(), c_1);
+    let (x_2, y_2) = context::sm2_p256().scalar_mult(c_1.x, c_1.y, private_key.as_array().to_vec());
+    (AffinePoint { x: x_2, y: y_2 }.to_array(), valid)
+}
+
+/// Like `shared_point`, multiplying with gmsm unless `constant-time` is enabled.
+#[cfg(all(feature = "std", feature = "constant-time"))]
+pub(crate) fn shared_point_std(private_key: &PrivateKey, c_1: &PublicKey) -> ([u8; 64], bool) {
+    shared_point(context::curve(), private_key, c_1)
+}
+
+/// Recovers the plaintext from C2, or `None` if C3 does not match.
+fn open(x2y2: &[u8; 64], c_2: &[u8], c_3: &[u8; 32]) -> Option<Vec<u8>> {
+    let mut plaintext = vec![0; c_2.len()];
+    hash::kdf(x2y2, &mut plaintext);
+    if !c_2.is_empty() && plaintext.iter().all(|b| *b == 0) {
+        return None;
+    }
+    plaintext.iter_mut().zip(c_2).for_each(|(m, c)| *m ^= c);
+
+    if !bool::from(hash::c3(x2y2, &plaintext).ct_eq(c_3)) {
+        return None;
+    }
+
+    Some(plaintext)
+}
+
+pub fn decrypt_with_options(
+    private_key: &PrivateKey,
+    msg: impl AsRef<[u8]>,
+    options: &DecodeOptions,
+) -> Result<Vec<u8>, Error> {
+    let ciphertext = Ciphertext::decode(msg, options)?;
+    decrypt(private_key, ciphertext.to_bytes(options.mode), options.mode)
+}
+
 #[cfg(test)]
 pub(crate) const PRIVATE_KEY: &str =
     "7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D";
@@ -129,6 +542,9 @@ pub(crate) const PUBLIC_KEY_Y: &str =
 mod tests {
     use super::*;
 
+    use rand::{rngs::StdRng, SeedableRng as _};
+
+    #[cfg(feature = "std")]
     #[test]
     fn test_sign_and_verify() {
         let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
@@ -136,11 +552,171 @@ mod tests {
         let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
 
         let msg = "TEST";
-        let signature = sign(&private_key, msg, None);
+        let signature = sign(&private_key, msg, None).unwrap();
         let ret = verify(&public_key, msg, None, &signature).unwrap();
         assert!(ret);
+
+        let mut bytes = [0; 64];
+        sign_into(&private_key, msg, None, &mut bytes).unwrap();
+        assert!(verify_strict(&public_key, msg, None, bytes).is_ok());
+
+        let user_id = "1".repeat(USER_ID_MAX_LEN + 1);
+        assert!(matches!(
+            sign(&private_key, msg, user_id.as_bytes()),
+            Err(Error::InvalidUserId)
+        ));
+        assert!(matches!(
+            verify(&public_key, msg, user_id.as_bytes(), &signature),
+            Err(Error::InvalidUserId)
+        ));
+    }
+
+    #[test]
+    fn test_sign_with_rng_and_verify() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        let msg = "TEST";
+        for user_id in [
+            None,
+            Some(b"1234567812345678".as_slice()),
+            Some(b"".as_slice()),
+        ] {
+            let signature = sign_with_rng(&mut rng, &private_key, msg, user_id).unwrap();
+            assert!(verify_via_soft(&public_key, msg, user_id, &signature).unwrap());
+            assert!(!verify_via_soft(&public_key, "TEST2", user_id, &signature).unwrap());
+            assert!(verify(&public_key, msg, user_id, &signature).unwrap());
+        }
+
+        // Same seed, same signature
+        assert_eq!(
+            sign_with_rng(&mut StdRng::seed_from_u64(1), &private_key, msg, None).unwrap(),
+            sign_with_rng(&mut StdRng::seed_from_u64(1), &private_key, msg, None).unwrap()
+        );
+
+        // From cncs-sm2-php/test.php
+        let signature = Signature::from_concated_hex_str("7AC28527C6B049FF74C42DE1541DB6BA34FEFE843A65BDAD49292B2830A85100202004346D91D896EF8B363EF42AD787D80F8815FA08FF2C5C204B083DF308F3").unwrap();
+        assert!(verify_via_soft(
+            &public_key,
+            "TEST",
+            b"1234567812345678".as_slice(),
+            &signature
+        )
+        .unwrap());
+        assert!(verify_via_soft(&public_key, "TEST", None, &signature).unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_matches_libsm() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let sig_ctx = libsm::sm2::signature::SigCtx::new();
+
+        for i in 0..10 {
+            let private_key =
+                PrivateKey::from_biguint(&curve::random_scalar(&mut rng, context::curve().n()))
+                    .unwrap();
+            let public_key = private_key.public_key();
+            let pk = libsm::sm2::ecc::Point::try_from(&public_key).unwrap();
+
+            let msg = alloc::format!("message {i}");
+            let signature = sign_with_rng(&mut rng, &private_key, &msg, None).unwrap();
+            let mut tampered = signature.to_concated_array();
+            tampered[i] ^= 0x01;
+            let candidates = [
+                signature.to_owned(),
+                Signature::new(*signature.s(), *signature.r()),
+                Signature::from_concated_bytes(&tampered).unwrap(),
+            ];
+
+            for signature in &candidates {
+                let expected = sig_ctx.verify(
+                    msg.as_bytes(),
+                    &pk,
+                    &libsm::sm2::signature::Signature::try_from(signature).unwrap(),
+                );
+                assert_eq!(
+                    verify(&public_key, &msg, None, signature).unwrap(),
+                    expected
+                );
+                assert_eq!(
+                    verify_via_soft(&public_key, &msg, None, signature).unwrap(),
+                    expected
+                );
+            }
+        }
     }
 
+    #[test]
+    fn test_verify_rejects_out_of_range_signature() {
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        let mut one = [0; 32];
+        one[31] = 1;
+        for signature in [
+            Signature::new([0; 32], one),
+            Signature::new(curve::N, one),
+            Signature::new(one, [0xFF; 32]),
+        ] {
+            assert!(matches!(
+                verify_via_soft(&public_key, "TEST", None, &signature),
+                Err(Error::InvalidSignature)
+            ));
+            assert!(matches!(
+                verify(&public_key, "TEST", None, &signature),
+                Err(Error::InvalidSignature)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_verify_strict() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        let msg = "TEST";
+        let signature = sign_with_rng(&mut rng, &private_key, msg, None)
+            .unwrap()
+            .to_concated_array();
+        assert_eq!(verify_strict(&public_key, msg, None, signature), Ok(()));
+
+        assert_eq!(
+            verify_strict(&public_key, "TEST2", None, signature),
+            Err(VerificationError::Mismatch)
+        );
+        assert_eq!(
+            verify_strict(&public_key, msg, None, &signature[1..]),
+            Err(VerificationError::MalformedSignature)
+        );
+        assert_eq!(
+            verify_strict(&public_key, msg, None, [0; 64]),
+            Err(VerificationError::SignatureOutOfRange)
+        );
+        assert_eq!(
+            verify_strict(
+                &PublicKey::from_hex_str(PUBLIC_KEY_Y, PUBLIC_KEY_X).unwrap(),
+                msg,
+                None,
+                signature
+            ),
+            Err(VerificationError::InvalidPublicKey)
+        );
+        assert_eq!(
+            verify_strict(
+                &public_key,
+                msg,
+                "1".repeat(USER_ID_MAX_LEN + 1).as_bytes(),
+                signature
+            ),
+            Err(VerificationError::InvalidUserId)
+        );
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn test_encrypt_and_decrypt() {
         let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
@@ -148,8 +724,202 @@ mod tests {
         let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
 
         let msg = "TEST";
-        let encrypt_bytes = encrypt(&public_key, msg, None);
-        let decrypt_bytes = decrypt(&private_key, encrypt_bytes, None);
+        let encrypt_bytes = encrypt(&public_key, msg, None).unwrap();
+        let decrypt_bytes = decrypt(&private_key, &encrypt_bytes, None).unwrap();
         assert_eq!(String::from_utf8(decrypt_bytes).unwrap(), msg);
+
+        assert!(matches!(
+            decrypt(&private_key, &encrypt_bytes[..96], None),
+            Err(Error::InvalidCiphertext)
+        ));
+        assert!(matches!(
+            decrypt(&private_key, &encrypt_bytes[1..], None),
+            Err(Error::InvalidCiphertext)
+        ));
+
+        let mut tampered = encrypt_bytes.to_owned();
+        *tampered.last_mut().unwrap() ^= 0x01;
+        assert!(matches!(
+            decrypt(&private_key, &tampered, None),
+            Err(Error::InvalidCiphertext)
+        ));
+    }
+
+    #[test]
+    fn test_encrypt_with_rng_and_decrypt() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        for mode in [EncryptMode::C1C2C3, EncryptMode::C1C3C2] {
+            for msg in [&b""[..], b"TEST", &[0xAB; 100]] {
+                let encrypt_bytes = encrypt_with_rng(&mut rng, &public_key, msg, mode).unwrap();
+                assert_eq!(encrypt_bytes.len(), 1 + 64 + 32 + msg.len());
+                assert_eq!(
+                    decrypt_via_soft(&private_key, &encrypt_bytes, mode).unwrap(),
+                    msg
+                );
+                #[cfg(feature = "std")]
+                assert_eq!(decrypt(&private_key, &encrypt_bytes, mode).unwrap(), msg);
+
+                let mut tampered = encrypt_bytes.to_owned();
+                *tampered.last_mut().unwrap() ^= 0x01;
+                assert!(matches!(
+                    decrypt_via_soft(&private_key, &tampered, mode),
+                    Err(Error::InvalidCiphertext)
+                ));
+            }
+        }
+
+        for mode in [EncryptMode::C1C2C3, EncryptMode::C1C3C2] {
+            let msg = b"TEST";
+            let mut out = [0xFF; 128];
+            let len = encrypt_with_rng_into(
+                &mut StdRng::seed_from_u64(1),
+                &public_key,
+                msg,
+                mode,
+                &mut out,
+            )
+            .unwrap();
+            assert_eq!(len, ciphertext_len(msg.len()));
+            assert_eq!(
+                out[..len],
+                encrypt_with_rng(&mut StdRng::seed_from_u64(1), &public_key, msg, mode).unwrap()
+            );
+            assert_eq!(out[len..], [0xFF; 128 - 101]);
+
+            assert!(matches!(
+                encrypt_with_rng_into(&mut rng, &public_key, msg, mode, &mut out[..len - 1]),
+                Err(Error::BufferTooSmall(101))
+            ));
+
+            #[cfg(feature = "std")]
+            {
+                let len = encrypt_into(&public_key, msg, mode, &mut out).unwrap();
+                assert_eq!(decrypt(&private_key, &out[..len], mode).unwrap(), msg);
+            }
+        }
+
+        // From cncs-sm2-php/test.php
+        for (mode, encrypt_bytes) in [
+            (EncryptMode::C1C3C2, "04846D268E472EDB42EB45233D92138E13EC354F47D38B56ACE5E504BAA9A5645644257ED439CBDC65809C8AD173BFE25BB4A31DBBF8516EB395159E8DCC399241A196954CEF2E75D2FCA1F4C3816ADD6CB15A38325BB1C87B8D2DAC18F8E3207AD18724FE"),
+            (EncryptMode::C1C2C3, "04F1234D8CEBBAFE1178797CD096AABBE94704F358559996C29E3339CD4C09B8F82F045C3B93DBFE7F55D1FB3901AFF8B023A0CC4901ABC3A7DB4EB44A97BEC7C3EE24B86CDE43B83B0D02D69A9F4D9C03E0E206BDC3108061F2CE5E67F3E5100398249EE5"),
+        ] {
+            let encrypt_bytes = hex_simd::decode_to_boxed_bytes(encrypt_bytes.as_bytes()).unwrap();
+            assert_eq!(
+                decrypt_via_soft(&private_key, &encrypt_bytes, mode).unwrap(),
+                b"TEST"
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_encrypt_and_decrypt_with_options() {
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        let msg = "TEST";
+        let encrypt_bytes =
+            encrypt_with_options(&public_key, msg, &types::EncodeOptions::SM_CRYPTO).unwrap();
+        assert_eq!(encrypt_bytes.len(), (64 + 32 + msg.len()) * 2);
+        assert!(encrypt_bytes.iter().all(|b| !b.is_ascii_uppercase()));
+        assert_eq!(
+            decrypt_with_options(&private_key, &encrypt_bytes, &DecodeOptions::SM_CRYPTO).unwrap(),
+            msg.as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_decrypt_auto() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        for msg in [&b""[..], b"TEST", &[0xAB; 100]] {
+            let ciphertext = Ciphertext::from_bytes(
+                &encrypt_with_rng(&mut rng, &public_key, msg, None).unwrap(),
+                None,
+            )
+            .unwrap();
+
+            let (plaintext, layout) = decrypt_auto(&private_key, ciphertext.to_der()).unwrap();
+            assert_eq!(plaintext, msg);
+            assert_eq!(layout, CiphertextLayout::Asn1);
+
+            for mode in [EncryptMode::C1C3C2, EncryptMode::C1C2C3] {
+                let (plaintext, layout) =
+                    decrypt_auto(&private_key, ciphertext.to_bytes(mode)).unwrap();
+                assert_eq!(plaintext, msg);
+                // An empty C2 makes both orders identical
+                if !msg.is_empty() {
+                    assert_eq!(layout, CiphertextLayout::Raw { mode, prefix: true });
+                }
+
+                let (plaintext, layout) =
+                    decrypt_auto(&private_key, ciphertext.to_bytes_without_prefix(mode)).unwrap();
+                assert_eq!(plaintext, msg);
+                if !msg.is_empty() {
+                    assert_eq!(
+                        layout,
+                        CiphertextLayout::Raw {
+                            mode,
+                            prefix: false
+                        }
+                    );
+                }
+            }
+
+            let mut tampered = ciphertext.to_bytes(None);
+            *tampered.last_mut().unwrap() ^= 0x01;
+            assert!(matches!(
+                decrypt_auto(&private_key, &tampered),
+                Err(Error::InvalidCiphertext)
+            ));
+        }
+
+        assert!(matches!(
+            decrypt_auto(&private_key, [0x04; 96]),
+            Err(Error::InvalidCiphertext)
+        ));
+    }
+
+    #[test]
+    fn test_decrypt_rejects_invalid_c1() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        let encrypt_bytes = encrypt_with_rng(&mut rng, &public_key, "TEST", None).unwrap();
+
+        let mut off_curve = encrypt_bytes.to_owned();
+        off_curve[64] ^= 0x01;
+        // (0, 0) is how infinity is commonly encoded
+        let mut zero = encrypt_bytes.to_owned();
+        zero[1..65].fill(0);
+        // Coordinates not below p
+        let mut out_of_range = encrypt_bytes.to_owned();
+        out_of_range[1..65].fill(0xFF);
+
+        for invalid in [off_curve, zero, out_of_range] {
+            assert!(matches!(
+                decrypt_via_soft(&private_key, &invalid, None),
+                Err(Error::InvalidCiphertext)
+            ));
+            assert!(matches!(
+                decrypt_auto(&private_key, &invalid),
+                Err(Error::InvalidCiphertext)
+            ));
+            #[cfg(feature = "std")]
+            assert!(matches!(
+                decrypt(&private_key, &invalid, None),
+                Err(Error::InvalidCiphertext)
+            ));
+        }
     }
 }
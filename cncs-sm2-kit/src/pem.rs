@@ -0,0 +1,69 @@
+//! PEM framing (RFC 7468) around the DER key formats in `der`.
+
+use alloc::{format, string::String, vec::Vec};
+
+pub(crate) const LABEL_PUBLIC_KEY: &str = "PUBLIC KEY";
+pub(crate) const LABEL_PRIVATE_KEY: &str = "PRIVATE KEY";
+/// SEC1, as written by `openssl ec` before and after OpenSSL 3.
+pub(crate) const LABELS_SEC1_PRIVATE_KEY: [&str; 2] = ["EC PRIVATE KEY", "SM2 PRIVATE KEY"];
+
+pub(crate) fn encode(label: &str, der: &[u8]) -> String {
+    let base64 = base64_simd::STANDARD.encode_to_string(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    // base64 is ASCII, so every 64-byte chunk is a whole line
+    for line in base64.as_bytes().chunks(64) {
+        pem.extend(line.iter().map(|b| *b as char));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// Returns the label and content of the first PEM block, ignoring text around it.
+pub(crate) fn decode(pem: &str) -> Option<(&str, Vec<u8>)> {
+    const BEGIN: &str = "-----BEGIN ";
+    const DASHES: &str = "-----";
+
+    let rest = &pem[pem.find(BEGIN)? + BEGIN.len()..];
+    let label_len = rest.find(DASHES)?;
+    let (label, rest) = (&rest[..label_len], &rest[label_len + DASHES.len()..]);
+
+    let body_len = rest.find(&format!("-----END {}-----", label))?;
+    let body = rest[..body_len]
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    let der = base64_simd::STANDARD.decode_to_vec(body).ok()?;
+    Some((label, der))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_decode() {
+        for len in [0, 1, 47, 48, 49, 200] {
+            let der = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+            let pem = encode(LABEL_PUBLIC_KEY, &der);
+            assert!(pem.lines().all(|line| line.len() <= 64));
+            assert_eq!(
+                decode(&format!(
+                    "text before\r\n{}text after",
+                    pem.replace('\n', "\r\n")
+                )),
+                Some((LABEL_PUBLIC_KEY, der))
+            );
+        }
+
+        for pem in [
+            "",
+            "-----BEGIN PUBLIC KEY-----\nAAAA\n",
+            "-----BEGIN PUBLIC KEY-----\nAAAA\n-----END PRIVATE KEY-----\n",
+            "-----BEGIN PUBLIC KEY-----\n!!!!\n-----END PUBLIC KEY-----\n",
+            "-----BEGIN PUBLIC KEY",
+        ] {
+            assert_eq!(decode(pem), None);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Encryption over `Read` / `Write`, for messages too large to hold in memory.
+//!
+//! The output is byte-identical to `encrypt_with_rng` and `decrypt` on the same input.
+//! `encrypt` reads its input once. `decrypt` must not write any plaintext before C3 is
+//! checked, and C3 covers all of C2, so it reads its input twice and needs `Seek`.
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use rand_core::{CryptoRng, RngCore};
+use sm3::{Digest as _, Sm3};
+use subtle::ConstantTimeEq as _;
+
+use crate::{
+    hash::Kdf,
+    types::{EncryptMode, PrivateKey, PublicKey},
+    Error,
+};
+
+const CHUNK_LEN: usize = 64 * 1024;
+
+pub fn encrypt(
+    public_key: &PublicKey,
+    reader: impl Read,
+    mode: impl Into<Option<EncryptMode>>,
+    writer: impl Write,
+) -> Result<u64, Error> {
+    encrypt_with_rng(&mut rand_core::OsRng, public_key, reader, mode, writer)
+}
+
+/// Encrypts `reader` to its end and returns the ciphertext length.
+///
+/// C1C2C3 streams in constant memory. C1C3C2 writes C3 before C2, so it holds C2 in memory
+/// until the message is hashed.
+pub fn encrypt_with_rng(
+    rng: &mut (impl CryptoRng + RngCore),
+    public_key: &PublicKey,
+    mut reader: impl Read,
+    mode: impl Into<Option<EncryptMode>>,
+    mut writer: impl Write,
+) -> Result<u64, Error> {
+    let mode: EncryptMode = mode.into().unwrap_or_default();
+
+    public_key.validate()?;
+
+    // k is redrawn while KDF(x2 || y2, klen) is all zero, and then so is its first block.
+    // Reading that far ahead is enough, and exact for shorter messages.
+    let mut first = [0; 32];
+    let first_len = read_up_to(&mut reader, &mut first)?;
+    let mut reader = first[..first_len].chain(reader);
+
+    let (c_1, x2y2) = crate::ephemeral_points(rng, public_key, first_len as u64);
+    writer.write_all(&[0x04])?;
+    writer.write_all(&c_1)?;
+
+    let mut kdf = Kdf::new(&x2y2);
+    // C3 = SM3(x2 || M || y2)
+    let mut hasher = Sm3::new();
+    hasher.update(&x2y2[..32]);
+
+    let msg_len = match mode {
+        EncryptMode::C1C3C2 => {
+            let mut c_2 = vec![];
+            reader.read_to_end(&mut c_2)?;
+            hasher.update(&c_2);
+            hasher.update(&x2y2[32..]);
+            writer.write_all(&hasher.finalize())?;
+
+            kdf.apply(&mut c_2);
+            writer.write_all(&c_2)?;
+            c_2.len() as u64
+        }
+        EncryptMode::C1C2C3 => {
+            let mut buf = vec![0; CHUNK_LEN];
+            let msg_len = for_each_chunk_to_end(&mut reader, &mut buf, |chunk| {
+                hasher.update(&*chunk);
+                kdf.apply(chunk);
+                writer.write_all(chunk)
+            })?;
+            hasher.update(&x2y2[32..]);
+            writer.write_all(&hasher.finalize())?;
+            msg_len
+        }
+    };
+
+    Ok(crate::ciphertext_len(0) as u64 + msg_len)
+}
+
+/// Decrypts `reader` from its current position to the end and returns the plaintext length.
+///
+/// # Security
+///
+/// The ciphertext is read twice. The first pass checks C1 and C3 without writing anything,
+/// the second decrypts C2 to `writer`, so forged plaintext never reaches it. `reader` must
+/// not change between the passes, e.g. a file being appended to, as the second pass is not
+/// checked again.
+pub fn decrypt(
+    private_key: &PrivateKey,
+    mut reader: impl Read + Seek,
+    mode: impl Into<Option<EncryptMode>>,
+    mut writer: impl Write,
+) -> Result<u64, Error> {
+    let mode: EncryptMode = mode.into().unwrap_or_default();
+
+    private_key.validate()?;
+
+    let start = reader.stream_position()?;
+    let msg_len = reader
+        .seek(SeekFrom::End(0))?
+        .saturating_sub(start)
+        .checked_sub(crate::ciphertext_len(0) as u64)
+        .ok_or(Error::InvalidCiphertext)?;
+    reader.seek(SeekFrom::Start(start))?;
+
+    let mut head = [0; 1 + 64];
+    read_head(&mut reader, &mut head)?;
+    let c_1 = match head.split_first() {
+        Some((0x04, c_1)) => PublicKey::from_concated_bytes(c_1)?,
+        _ => return Err(Error::InvalidCiphertext),
+    };
+    let (x2y2, valid) = crate::shared_point_std(private_key, &c_1);
+
+    let mut c_3 = [0; 32];
+    let c_2_start = match mode {
+        EncryptMode::C1C3C2 => {
+            read_head(&mut reader, &mut c_3)?;
+            start + head.len() as u64 + 32
+        }
+        EncryptMode::C1C2C3 => {
+            reader.seek(SeekFrom::Start(start + head.len() as u64 + msg_len))?;
+            read_head(&mut reader, &mut c_3)?;
+            start + head.len() as u64
+        }
+    };
+
+    let mut buf = vec![0; CHUNK_LEN];
+    let mut kdf = Kdf::new(&x2y2);
+    let mut hasher = Sm3::new();
+    hasher.update(&x2y2[..32]);
+    reader.seek(SeekFrom::Start(c_2_start))?;
+    for_each_chunk(&mut reader, msg_len, &mut buf, |chunk| {
+        kdf.apply(chunk);
+        hasher.update(&*chunk);
+        Ok(())
+    })?;
+    hasher.update(&x2y2[32..]);
+    let c3_ok = bool::from(hasher.finalize().as_slice().ct_eq(&c_3));
+    if !valid || !c3_ok || (msg_len > 0 && kdf.is_zero()) {
+        return Err(Error::InvalidCiphertext);
+    }
+
+    let mut kdf = Kdf::new(&x2y2);
+    reader.seek(SeekFrom::Start(c_2_start))?;
+    for_each_chunk(&mut reader, msg_len, &mut buf, |chunk| {
+        kdf.apply(chunk);
+        writer.write_all(chunk)
+    })?;
+
+    Ok(msg_len)
+}
+
+fn for_each_chunk(
+    reader: &mut impl Read,
+    len: u64,
+    buf: &mut [u8],
+    mut f: impl FnMut(&mut [u8]) -> io::Result<()>,
+) -> Result<(), Error> {
+    let mut remaining = len;
+    while remaining > 0 {
+        let len = remaining.min(buf.len() as u64) as usize;
+        let chunk = &mut buf[..len];
+        reader.read_exact(chunk)?;
+        f(chunk)?;
+        remaining -= chunk.len() as u64;
+    }
+    Ok(())
+}
+
+fn for_each_chunk_to_end(
+    reader: &mut impl Read,
+    buf: &mut [u8],
+    mut f: impl FnMut(&mut [u8]) -> io::Result<()>,
+) -> Result<u64, Error> {
+    let mut len = 0;
+    loop {
+        let read = match reader.read(buf) {
+            Ok(0) => return Ok(len),
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        f(&mut buf[..read])?;
+        len += read as u64;
+    }
+}
+
+/// Fills `buf` unless `reader` ends first, and returns how much was read.
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(len)
+}
+
+/// Like `read_exact`, but a short read is a malformed ciphertext.
+fn read_head(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), Error> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => Error::InvalidCiphertext,
+        _ => Error::Io(err),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use rand::{rngs::StdRng, SeedableRng as _};
+
+    use crate::{PRIVATE_KEY, PUBLIC_KEY_X, PUBLIC_KEY_Y};
+
+    #[test]
+    fn test_matches_in_memory() {
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        for mode in [EncryptMode::C1C2C3, EncryptMode::C1C3C2] {
+            for msg_len in [0, 1, 31, 32, 33, CHUNK_LEN + 5] {
+                let msg = (0..msg_len).map(|i| i as u8).collect::<Vec<_>>();
+
+                let expected =
+                    crate::encrypt_with_rng(&mut StdRng::seed_from_u64(0), &public_key, &msg, mode)
+                        .unwrap();
+                let mut ciphertext = vec![];
+                let len = encrypt_with_rng(
+                    &mut StdRng::seed_from_u64(0),
+                    &public_key,
+                    Cursor::new(&msg),
+                    mode,
+                    &mut ciphertext,
+                )
+                .unwrap();
+                assert_eq!(ciphertext, expected);
+                assert_eq!(len, ciphertext.len() as u64);
+
+                let mut plaintext = vec![];
+                let len =
+                    decrypt(&private_key, Cursor::new(&ciphertext), mode, &mut plaintext).unwrap();
+                assert_eq!(plaintext, msg);
+                assert_eq!(len, msg.len() as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encrypt_from_position() {
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        for mode in [EncryptMode::C1C2C3, EncryptMode::C1C3C2] {
+            let mut reader = Cursor::new(b"HEADERTEST");
+            reader.set_position(6);
+            let mut ciphertext = vec![];
+            encrypt(&public_key, reader, mode, &mut ciphertext).unwrap();
+            assert_eq!(
+                crate::decrypt(&private_key, &ciphertext, mode).unwrap(),
+                b"TEST"
+            );
+        }
+    }
+
+    #[test]
+    fn test_encrypt_short_reads() {
+        // A pipe, say, hands over a few bytes at a time and cannot seek
+        struct Trickle<'a>(&'a [u8]);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let len = buf.len().min(self.0.len()).min(7);
+                buf[..len].copy_from_slice(&self.0[..len]);
+                self.0 = &self.0[len..];
+                Ok(len)
+            }
+        }
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+        let msg = (0..100).collect::<Vec<u8>>();
+        for mode in [EncryptMode::C1C2C3, EncryptMode::C1C3C2] {
+            let expected =
+                crate::encrypt_with_rng(&mut StdRng::seed_from_u64(1), &public_key, &msg, mode)
+                    .unwrap();
+            let mut ciphertext = vec![];
+            encrypt_with_rng(
+                &mut StdRng::seed_from_u64(1),
+                &public_key,
+                Trickle(&msg),
+                mode,
+                &mut ciphertext,
+            )
+            .unwrap();
+            assert_eq!(ciphertext, expected);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_invalid() {
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        for mode in [EncryptMode::C1C2C3, EncryptMode::C1C3C2] {
+            let ciphertext = crate::encrypt(&public_key, "TEST", mode).unwrap();
+
+            let mut tampered = ciphertext.to_owned();
+            *tampered.last_mut().unwrap() ^= 0x01;
+            let mut compressed = ciphertext.to_owned();
+            compressed[0] = 0x02;
+            for bytes in [&tampered[..], &compressed, &ciphertext[..96], &[]] {
+                let mut plaintext = vec![];
+                assert!(matches!(
+                    decrypt(&private_key, Cursor::new(bytes), mode, &mut plaintext),
+                    Err(Error::InvalidCiphertext)
+                ));
+                assert!(plaintext.is_empty());
+            }
+        }
+    }
+}
@@ -0,0 +1,441 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    der::{self, Reader},
+    types::{EncryptMode, PublicKey},
+    Error,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum CiphertextEncoding {
+    #[default]
+    Binary,
+    /// Hex of either case is accepted when decoding.
+    UpperHex,
+    LowerHex,
+    Base64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct DecodeOptions {
+    pub mode: EncryptMode,
+    pub encoding: CiphertextEncoding,
+    /// Whether C1 carries the `04` prefix, `None` detects it.
+    pub prefix: Option<bool>,
+}
+impl DecodeOptions {
+    /// `sm2.doDecrypt` of the JavaScript `sm-crypto` library with `cipherMode = 1`.
+    pub const SM_CRYPTO: Self = Self {
+        mode: EncryptMode::C1C3C2,
+        encoding: CiphertextEncoding::LowerHex,
+        prefix: Some(false),
+    };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EncodeOptions {
+    pub mode: EncryptMode,
+    pub encoding: CiphertextEncoding,
+    pub prefix: bool,
+}
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            mode: EncryptMode::default(),
+            encoding: CiphertextEncoding::default(),
+            prefix: true,
+        }
+    }
+}
+impl EncodeOptions {
+    /// `sm2.doEncrypt` of the JavaScript `sm-crypto` library with `cipherMode = 1`.
+    pub const SM_CRYPTO: Self = Self {
+        mode: EncryptMode::C1C3C2,
+        encoding: CiphertextEncoding::LowerHex,
+        prefix: false,
+    };
+}
+
+/// The wire layout a ciphertext was found in, see `decrypt_auto`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CiphertextLayout {
+    Raw { mode: EncryptMode, prefix: bool },
+    Asn1,
+}
+impl fmt::Display for CiphertextLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CiphertextLayout::Raw { mode, prefix: true } => write!(f, "{:?}", mode),
+            CiphertextLayout::Raw {
+                mode,
+                prefix: false,
+            } => write!(f, "{:?} without 04 prefix", mode),
+            CiphertextLayout::Asn1 => write!(f, "ASN.1 DER"),
+        }
+    }
+}
+
+/// C1 || C3 || C2 as defined in GB/T 32918.4-2016, independent of the wire layout.
+///
+/// Unprefixed like `PublicKey` and `Signature`, `Sm2Ciphertext` is an alias.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ciphertext {
+    pub c1: PublicKey,
+    pub c3: [u8; 32],
+    pub c2: Vec<u8>,
+}
+
+/// Alias of `Ciphertext`.
+pub type Sm2Ciphertext = Ciphertext;
+
+impl Ciphertext {
+    pub fn new(c1: PublicKey, c3: [u8; 32], c2: Vec<u8>) -> Self {
+        Self { c1, c3, c2 }
+    }
+
+    /// Parses `04 || C1 || C3 || C2` or `04 || C1 || C2 || C3`.
+    pub fn from_bytes(bytes: &[u8], mode: impl Into<Option<EncryptMode>>) -> Result<Self, Error> {
+        let (c1, c_3, c_2) = Self::split(bytes, mode)?;
+        Ok(Self::new(c1, *c_3, c_2.to_vec()))
+    }
+
+    /// Parses `C1 || C3 || C2` or `C1 || C2 || C3`, where C1 lacks the `04` prefix.
+    pub fn from_bytes_without_prefix(
+        bytes: &[u8],
+        mode: impl Into<Option<EncryptMode>>,
+    ) -> Result<Self, Error> {
+        let (c1, c_3, c_2) = Self::split_without_prefix(bytes, mode)?;
+        Ok(Self::new(c1, *c_3, c_2.to_vec()))
+    }
+
+    /// Borrows C3 and C2 instead of copying them.
+    pub(crate) fn split(
+        bytes: &[u8],
+        mode: impl Into<Option<EncryptMode>>,
+    ) -> Result<(PublicKey, &[u8; 32], &[u8]), Error> {
+        match bytes.split_first() {
+            Some((0x04, bytes)) => Self::split_without_prefix(bytes, mode),
+            _ => Err(Error::InvalidCiphertext),
+        }
+    }
+
+    pub(crate) fn split_without_prefix(
+        bytes: &[u8],
+        mode: impl Into<Option<EncryptMode>>,
+    ) -> Result<(PublicKey, &[u8; 32], &[u8]), Error> {
+        let mode: EncryptMode = mode.into().unwrap_or_default();
+
+        if bytes.len() < 64 + 32 {
+            return Err(Error::InvalidCiphertext);
+        }
+        let (c_1, rest) = bytes.split_at(64);
+        let (c_2, c_3) = match mode {
+            EncryptMode::C1C2C3 => rest.split_at(rest.len() - 32),
+            EncryptMode::C1C3C2 => {
+                let (c_3, c_2) = rest.split_at(32);
+                (c_2, c_3)
+            }
+        };
+
+        Ok((
+            PublicKey::from_concated_bytes(c_1)?,
+            c_3.try_into().expect("32 bytes"),
+            c_2,
+        ))
+    }
+
+    /// Parses the ASN.1 DER `SM2Cipher` structure of GB/T 35276-2017.
+    pub fn from_der(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader::new(bytes);
+        let sequence = reader
+            .read(der::TAG_SEQUENCE)
+            .ok_or(Error::InvalidCiphertext)?;
+        if !reader.is_empty() {
+            return Err(Error::InvalidCiphertext);
+        }
+
+        let mut reader = Reader::new(sequence);
+        let x = reader.read_unsigned_integer();
+        let y = reader.read_unsigned_integer();
+        let c_3 = reader.read(der::TAG_OCTET_STRING);
+        let c_2 = reader.read(der::TAG_OCTET_STRING);
+        match (x, y, c_3, c_2) {
+            (Some(x), Some(y), Some(c_3), Some(c_2))
+                if reader.is_empty() && x.len() <= 32 && y.len() <= 32 && c_3.len() == 32 =>
+            {
+                let mut c3 = [0; 32];
+                c3.copy_from_slice(c_3);
+                Ok(Self {
+                    c1: PublicKey::from_bytes(x, y).map_err(|_| Error::InvalidCiphertext)?,
+                    c3,
+                    c2: c_2.to_vec(),
+                })
+            }
+            _ => Err(Error::InvalidCiphertext),
+        }
+    }
+
+    pub fn decode(input: impl AsRef<[u8]>, options: &DecodeOptions) -> Result<Self, Error> {
+        let input = input.as_ref();
+        let decoded;
+        let bytes = match options.encoding {
+            CiphertextEncoding::Binary => input,
+            CiphertextEncoding::UpperHex | CiphertextEncoding::LowerHex => {
+                decoded =
+                    hex_simd::decode_to_boxed_bytes(input).map_err(|_| Error::InvalidCiphertext)?;
+                &decoded[..]
+            }
+            CiphertextEncoding::Base64 => {
+                decoded = base64_simd::STANDARD
+                    .decode_to_vec(input)
+                    .map_err(|_| Error::InvalidCiphertext)?
+                    .into_boxed_slice();
+                &decoded[..]
+            }
+        };
+
+        match options.prefix {
+            Some(true) => Self::from_bytes(bytes, options.mode),
+            Some(false) => Self::from_bytes_without_prefix(bytes, options.mode),
+            None => {
+                // Only one of the two readings yields a C1 on the curve, barring negligible chance
+                match Self::from_bytes(bytes, options.mode) {
+                    Ok(ciphertext) if ciphertext.c1.validate().is_ok() => Ok(ciphertext),
+                    _ => Self::from_bytes_without_prefix(bytes, options.mode),
+                }
+            }
+        }
+    }
+
+    pub fn encode(&self, options: &EncodeOptions) -> Vec<u8> {
+        let bytes = if options.prefix {
+            self.to_bytes(options.mode)
+        } else {
+            self.to_bytes_without_prefix(options.mode)
+        };
+
+        match options.encoding {
+            CiphertextEncoding::Binary => bytes,
+            CiphertextEncoding::UpperHex => {
+                hex_simd::encode_to_boxed_str(&bytes, hex_simd::AsciiCase::Upper)
+                    .into_boxed_bytes()
+                    .into()
+            }
+            CiphertextEncoding::LowerHex => {
+                hex_simd::encode_to_boxed_str(&bytes, hex_simd::AsciiCase::Lower)
+                    .into_boxed_bytes()
+                    .into()
+            }
+            CiphertextEncoding::Base64 => base64_simd::STANDARD.encode_to_string(&bytes).into(),
+        }
+    }
+
+    pub fn to_bytes(&self, mode: impl Into<Option<EncryptMode>>) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + 64 + 32 + self.c2.len());
+        bytes.push(0x04);
+        self.write_without_prefix(&mut bytes, mode.into().unwrap_or_default());
+        bytes
+    }
+
+    pub fn to_bytes_without_prefix(&self, mode: impl Into<Option<EncryptMode>>) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64 + 32 + self.c2.len());
+        self.write_without_prefix(&mut bytes, mode.into().unwrap_or_default());
+        bytes
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut sequence = Vec::with_capacity(2 * (2 + 33) + 2 + 32 + 6 + self.c2.len());
+        der::write_unsigned_integer(&mut sequence, self.c1.x());
+        der::write_unsigned_integer(&mut sequence, self.c1.y());
+        der::write(&mut sequence, der::TAG_OCTET_STRING, &self.c3);
+        der::write(&mut sequence, der::TAG_OCTET_STRING, &self.c2);
+
+        let mut bytes = Vec::with_capacity(6 + sequence.len());
+        der::write(&mut bytes, der::TAG_SEQUENCE, &sequence);
+        bytes
+    }
+
+    fn write_without_prefix(&self, bytes: &mut Vec<u8>, mode: EncryptMode) {
+        bytes.extend_from_slice(&self.c1.to_concated_array());
+        match mode {
+            EncryptMode::C1C2C3 => {
+                bytes.extend_from_slice(&self.c2);
+                bytes.extend_from_slice(&self.c3);
+            }
+            EncryptMode::C1C3C2 => {
+                bytes.extend_from_slice(&self.c3);
+                bytes.extend_from_slice(&self.c2);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{types::PrivateKey, PRIVATE_KEY};
+
+    // From cncs-sm2-php/test.php
+    const C1C3C2: &str = "04846D268E472EDB42EB45233D92138E13EC354F47D38B56ACE5E504BAA9A5645644257ED439CBDC65809C8AD173BFE25BB4A31DBBF8516EB395159E8DCC399241A196954CEF2E75D2FCA1F4C3816ADD6CB15A38325BB1C87B8D2DAC18F8E3207AD18724FE";
+    const C1C2C3: &str = "04F1234D8CEBBAFE1178797CD096AABBE94704F358559996C29E3339CD4C09B8F82F045C3B93DBFE7F55D1FB3901AFF8B023A0CC4901ABC3A7DB4EB44A97BEC7C3EE24B86CDE43B83B0D02D69A9F4D9C03E0E206BDC3108061F2CE5E67F3E5100398249EE5";
+
+    fn decode(hex_str: &str) -> Vec<u8> {
+        hex_simd::decode_to_boxed_bytes(hex_str.as_bytes())
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn test_convert_mode() {
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+        let bytes = decode(C1C2C3);
+        let ciphertext = Ciphertext::from_bytes(&bytes, EncryptMode::C1C2C3).unwrap();
+        assert_eq!(ciphertext.c2.len(), 4);
+        assert_eq!(ciphertext.to_bytes(EncryptMode::C1C2C3), bytes);
+        assert_eq!(
+            ciphertext.to_bytes_without_prefix(EncryptMode::C1C2C3),
+            &bytes[1..]
+        );
+
+        let converted = ciphertext.to_bytes(EncryptMode::C1C3C2);
+        assert_eq!(
+            crate::decrypt_via_soft(&private_key, &converted, EncryptMode::C1C3C2).unwrap(),
+            b"TEST"
+        );
+        assert_eq!(
+            Ciphertext::from_bytes(&converted, None).unwrap(),
+            ciphertext
+        );
+
+        let bytes = decode(C1C3C2);
+        let ciphertext = Ciphertext::from_bytes(&bytes, None).unwrap();
+        assert_eq!(ciphertext.to_bytes(None), bytes);
+        assert_eq!(
+            Ciphertext::from_bytes_without_prefix(&bytes[1..], None).unwrap(),
+            ciphertext
+        );
+    }
+
+    #[test]
+    fn test_der() {
+        let bytes = decode(C1C3C2);
+        let ciphertext = Ciphertext::from_bytes(&bytes, None).unwrap();
+
+        let der_bytes = ciphertext.to_der();
+        // x has its high bit set and needs a leading zero, y does not
+        assert_eq!(&der_bytes[..5], &[0x30, 0x6D, 0x02, 0x21, 0x00]);
+        assert_eq!(&der_bytes[37..39], &[0x02, 0x20]);
+        assert_eq!(&der_bytes[71..73], &[0x04, 0x20]);
+        assert_eq!(&der_bytes[105..107], &[0x04, 0x04]);
+        assert_eq!(Ciphertext::from_der(&der_bytes).unwrap(), ciphertext);
+
+        let mut trailing = der_bytes.to_owned();
+        trailing.push(0x00);
+        assert!(matches!(
+            Ciphertext::from_der(&trailing),
+            Err(Error::InvalidCiphertext)
+        ));
+        assert!(matches!(
+            Ciphertext::from_der(&der_bytes[..der_bytes.len() - 1]),
+            Err(Error::InvalidCiphertext)
+        ));
+    }
+
+    #[test]
+    fn test_decode_and_encode() {
+        let bytes = decode(C1C3C2);
+        let ciphertext = Ciphertext::from_bytes(&bytes, None).unwrap();
+
+        let sm_crypto = ciphertext.encode(&EncodeOptions::SM_CRYPTO);
+        assert_eq!(sm_crypto, C1C3C2[2..].to_lowercase().as_bytes());
+        assert_eq!(
+            Ciphertext::decode(&sm_crypto, &DecodeOptions::SM_CRYPTO).unwrap(),
+            ciphertext
+        );
+
+        for encoding in [
+            CiphertextEncoding::Binary,
+            CiphertextEncoding::UpperHex,
+            CiphertextEncoding::LowerHex,
+            CiphertextEncoding::Base64,
+        ] {
+            for prefix in [true, false] {
+                let encoded = ciphertext.encode(&EncodeOptions {
+                    encoding,
+                    prefix,
+                    ..Default::default()
+                });
+                for decode_prefix in [Some(prefix), None] {
+                    let options = DecodeOptions {
+                        encoding,
+                        prefix: decode_prefix,
+                        ..Default::default()
+                    };
+                    assert_eq!(Ciphertext::decode(&encoded, &options).unwrap(), ciphertext);
+                }
+            }
+        }
+
+        assert_eq!(
+            ciphertext.encode(&EncodeOptions {
+                encoding: CiphertextEncoding::UpperHex,
+                ..Default::default()
+            }),
+            C1C3C2.as_bytes()
+        );
+        // Hex of either case decodes
+        let options = DecodeOptions {
+            encoding: CiphertextEncoding::UpperHex,
+            ..Default::default()
+        };
+        assert_eq!(
+            Ciphertext::decode(C1C3C2.to_lowercase(), &options).unwrap(),
+            ciphertext
+        );
+        assert!(matches!(
+            Ciphertext::decode("XYZ", &options),
+            Err(Error::InvalidCiphertext)
+        ));
+    }
+
+    #[test]
+    fn test_layout_display() {
+        assert_eq!(
+            CiphertextLayout::Raw {
+                mode: EncryptMode::C1C2C3,
+                prefix: true
+            }
+            .to_string(),
+            "C1C2C3"
+        );
+        assert_eq!(
+            CiphertextLayout::Raw {
+                mode: EncryptMode::C1C3C2,
+                prefix: false
+            }
+            .to_string(),
+            "C1C3C2 without 04 prefix"
+        );
+        assert_eq!(CiphertextLayout::Asn1.to_string(), "ASN.1 DER");
+    }
+
+    #[test]
+    fn test_invalid() {
+        let bytes = decode(C1C3C2);
+        assert!(matches!(
+            Ciphertext::from_bytes(&bytes[..96], None),
+            Err(Error::InvalidCiphertext)
+        ));
+        assert!(matches!(
+            Ciphertext::from_bytes(&bytes[1..], None),
+            Err(Error::InvalidCiphertext)
+        ));
+        assert!(Ciphertext::from_bytes(&bytes[..97], None)
+            .unwrap()
+            .c2
+            .is_empty());
+    }
+}
@@ -1,14 +1,15 @@
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Byte order of C1, C3 and C2.
+///
+/// The bindings number C1C3C2 `0` and C1C2C3 `1`, as the `mode` of the PHP extension does.
+/// The `cipherMode` of sm-crypto is the reverse, `1` for C1C3C2 and `0` for C1C2C3.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 pub enum EncryptMode {
     C1C2C3,
+    #[default]
     C1C3C2,
 }
-impl Default for EncryptMode {
-    fn default() -> Self {
-        Self::C1C3C2
-    }
-}
 
+#[cfg(feature = "std")]
 impl EncryptMode {
     pub fn to_gmsm_mode(&self) -> usize {
         match self {
@@ -1,20 +1,76 @@
+pub mod ciphertext;
 pub mod encrypt_mode;
 pub mod private_key;
 pub mod public_key;
 pub mod signature;
+pub mod verifying_key;
 
+pub use ciphertext::{
+    Ciphertext, CiphertextEncoding, CiphertextLayout, DecodeOptions, EncodeOptions, Sm2Ciphertext,
+};
 pub use encrypt_mode::EncryptMode;
 pub use private_key::PrivateKey;
 pub use public_key::PublicKey;
 pub use signature::Signature;
+pub use verifying_key::VerifyingKey;
 
-pub(crate) fn to_bytes<const LEN: usize>(num: &num_bigint::BigUint) -> [u8; LEN] {
+use alloc::string::String;
+
+use num_bigint::BigUint;
+use num_traits::Num as _;
+
+use crate::Error;
+
+pub(crate) fn to_bytes<const LEN: usize>(num: &BigUint) -> [u8; LEN] {
     let data = num.to_bytes_be();
     let mut ret = [0; LEN];
     ret[LEN - data.len()..].copy_from_slice(&data);
     ret
 }
 
+/// Left-pads a big-endian number to `LEN` bytes, or `None` if it does not fit.
+pub(crate) fn to_array<const LEN: usize>(bytes: &[u8]) -> Option<[u8; LEN]> {
+    let skip = bytes.iter().take_while(|b| **b == 0).count();
+    let data = &bytes[skip..];
+    if data.len() > LEN {
+        return None;
+    }
+    let mut ret = [0; LEN];
+    ret[LEN - data.len()..].copy_from_slice(data);
+    Some(ret)
+}
+
+pub(crate) fn hex_str_to_array<const LEN: usize>(
+    hex_str: &str,
+) -> Result<Option<[u8; LEN]>, Error> {
+    let num = BigUint::from_str_radix(hex_str, 16)?;
+    Ok(to_array(&num.to_bytes_be()))
+}
+
+/// Splits a concatenated hex string at byte `mid`. A `mid` inside a multibyte character means
+/// fewer hex digits than bytes, reported as `InvalidLength` of the character count.
+pub(crate) fn split_hex_str(hex_str: &str, mid: usize) -> Result<(&str, &str), Error> {
+    match (hex_str.get(..mid), hex_str.get(mid..)) {
+        (Some(a), Some(b)) => Ok((a, b)),
+        _ => Err(Error::InvalidLength(hex_str.chars().count())),
+    }
+}
+
+pub(crate) fn concat(a: &[u8; 32], b: &[u8; 32]) -> [u8; 64] {
+    let mut ret = [0; 64];
+    ret[..32].copy_from_slice(a);
+    ret[32..].copy_from_slice(b);
+    ret
+}
+
+pub(crate) fn first_half(bytes: &[u8; 64]) -> &[u8; 32] {
+    bytes[..32].try_into().expect("32 bytes")
+}
+
+pub(crate) fn second_half(bytes: &[u8; 64]) -> &[u8; 32] {
+    bytes[32..].try_into().expect("32 bytes")
+}
+
 #[inline]
 pub(crate) fn to_hex_str(data: &[u8]) -> String {
     hex_simd::encode_to_boxed_str(data, hex_simd::AsciiCase::Upper).into()
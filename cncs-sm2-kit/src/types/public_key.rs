@@ -1,18 +1,24 @@
-use core::fmt;
+use alloc::{string::String, vec::Vec};
+use core::{fmt, str::FromStr};
 
-use num_bigint::{BigUint, ParseBigIntError};
-use num_traits::Num as _;
+use num_bigint::BigUint;
 
-#[derive(Clone)]
+use crate::{
+    backend::soft::AffinePoint,
+    der::{self, Reader},
+    pem, Error,
+};
+
+/// x || y, big-endian.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct PublicKey {
-    pub x: BigUint,
-    pub y: BigUint,
+    bytes: [u8; 64],
 }
 impl fmt::Debug for PublicKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PublicKey")
-            .field("x", &self.x.to_str_radix(16).to_uppercase())
-            .field("y", &self.y.to_str_radix(16).to_uppercase())
+            .field("x", &super::to_hex_str(self.x()))
+            .field("y", &super::to_hex_str(self.y()))
             .finish()
     }
 }
@@ -23,91 +29,225 @@ impl fmt::Display for PublicKey {
 }
 
 impl PublicKey {
-    pub fn new(x: BigUint, y: BigUint) -> Self {
-        Self { x, y }
+    pub fn new(x: [u8; 32], y: [u8; 32]) -> Self {
+        Self {
+            bytes: super::concat(&x, &y),
+        }
     }
 
-    pub fn from_bytes(x_bytes: &[u8], y_bytes: &[u8]) -> Self {
-        let x = BigUint::from_bytes_be(x_bytes);
-        let y = BigUint::from_bytes_be(y_bytes);
-        Self { x, y }
+    /// Accepts big-endian coordinates of at most 32 significant bytes each.
+    pub fn from_bytes(x_bytes: &[u8], y_bytes: &[u8]) -> Result<Self, Error> {
+        match (super::to_array(x_bytes), super::to_array(y_bytes)) {
+            (Some(x), Some(y)) => Ok(Self::new(x, y)),
+            _ => Err(Error::InvalidPublicKey),
+        }
+    }
+
+    pub fn from_hex_str(x_hex_str: &str, y_hex_str: &str) -> Result<Self, Error> {
+        match (
+            super::hex_str_to_array(x_hex_str)?,
+            super::hex_str_to_array(y_hex_str)?,
+        ) {
+            (Some(x), Some(y)) => Ok(Self::new(x, y)),
+            _ => Err(Error::InvalidPublicKey),
+        }
     }
 
-    pub fn from_hex_str(x_hex_str: &str, y_hex_str: &str) -> Result<Self, ParseBigIntError> {
-        let x = BigUint::from_str_radix(x_hex_str, 16)?;
-        let y = BigUint::from_str_radix(y_hex_str, 16)?;
-        Ok(Self { x, y })
+    pub fn from_biguint(x: &BigUint, y: &BigUint) -> Result<Self, Error> {
+        Self::from_bytes(&x.to_bytes_be(), &y.to_bytes_be())
     }
 
-    pub fn from_concated_hex_str(hex_str: &str) -> Result<Self, PublicKeyFromConcatedHexStrError> {
+    pub fn from_concated_hex_str(hex_str: &str) -> Result<Self, Error> {
         match hex_str.len() {
             130 => {
                 if !hex_str.starts_with("04") {
-                    return Err(PublicKeyFromConcatedHexStrError::Invalid);
+                    return Err(Error::InvalidPrefix);
                 }
-                Self::from_hex_str(&hex_str[2..66], &hex_str[66..])
-                    .map_err(PublicKeyFromConcatedHexStrError::ParseBigIntError)
+                let (x, y) = super::split_hex_str(&hex_str[2..], 64)?;
+                Self::from_hex_str(x, y)
+            }
+            128 => {
+                let (x, y) = super::split_hex_str(hex_str, 64)?;
+                Self::from_hex_str(x, y)
+            }
+            len => Err(Error::InvalidLength(len)),
+        }
+    }
+
+    pub fn from_concated_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        match bytes.len() {
+            65 => {
+                if bytes[0] != 0x04 {
+                    return Err(Error::InvalidPrefix);
+                }
+                Self::from_concated_bytes(&bytes[1..])
+            }
+            64 => {
+                let mut array = [0; 64];
+                array.copy_from_slice(bytes);
+                Ok(Self { bytes: array })
             }
-            128 => Self::from_hex_str(&hex_str[..64], &hex_str[64..])
-                .map_err(PublicKeyFromConcatedHexStrError::ParseBigIntError),
-            _ => Err(PublicKeyFromConcatedHexStrError::Invalid),
+            len => Err(Error::InvalidLength(len)),
         }
     }
 
+    /// Parses a SubjectPublicKeyInfo on the SM2 curve, as `openssl pkey -pubout` writes.
+    pub fn from_der(bytes: &[u8]) -> Result<Self, Error> {
+        let spki = der::read_only(bytes, der::TAG_SEQUENCE).ok_or(Error::Malformed)?;
+        let mut reader = Reader::new(spki);
+        der::read_sm2_algorithm(&mut reader).ok_or(Error::Malformed)?;
+        match reader.read(der::TAG_BIT_STRING) {
+            // No unused bits, then the uncompressed point
+            Some([0x00, point @ ..]) if reader.is_empty() && point.len() == 65 => {
+                Self::from_concated_bytes(point)
+            }
+            _ => Err(Error::Malformed),
+        }
+    }
+
+    pub fn from_pem(pem: &str) -> Result<Self, Error> {
+        match crate::pem::decode(pem) {
+            Some((pem::LABEL_PUBLIC_KEY, der)) => Self::from_der(&der),
+            _ => Err(Error::Malformed),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        if !crate::backend::vartime::is_on_curve(&self.bytes) {
+            return Err(Error::InvalidPublicKey);
+        }
+        Ok(())
+    }
+
+    pub fn x(&self) -> &[u8; 32] {
+        super::first_half(&self.bytes)
+    }
+
+    pub fn y(&self) -> &[u8; 32] {
+        super::second_half(&self.bytes)
+    }
+
+    pub fn as_concated_array(&self) -> &[u8; 64] {
+        &self.bytes
+    }
+
+    pub fn to_concated_array(&self) -> [u8; 64] {
+        self.bytes
+    }
+
     pub fn to_concated_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(64);
-        bytes.extend(super::to_bytes::<32>(&self.x));
-        bytes.extend(super::to_bytes::<32>(&self.y));
-        bytes
+        self.bytes.to_vec()
     }
 
     pub fn to_concated_hex_str(&self) -> String {
-        super::to_hex_str(&self.to_concated_bytes())
+        super::to_hex_str(&self.bytes)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut point = Vec::with_capacity(2 + 64);
+        point.extend_from_slice(&[0x00, 0x04]);
+        point.extend_from_slice(&self.bytes);
+
+        let mut spki = Vec::new();
+        der::write_sm2_algorithm(&mut spki);
+        der::write(&mut spki, der::TAG_BIT_STRING, &point);
+        let mut out = Vec::new();
+        der::write(&mut out, der::TAG_SEQUENCE, &spki);
+        out
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem::encode(pem::LABEL_PUBLIC_KEY, &self.to_der())
     }
 }
 
-#[derive(Debug)]
-pub enum PublicKeyFromConcatedHexStrError {
-    Invalid,
-    ParseBigIntError(ParseBigIntError),
+//
+//
+//
+impl FromStr for PublicKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_concated_hex_str(s)
+    }
 }
-impl fmt::Display for PublicKeyFromConcatedHexStrError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+
+impl TryFrom<&[u8]> for PublicKey {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_concated_bytes(bytes)
+    }
+}
+
+impl From<[u8; 64]> for PublicKey {
+    fn from(bytes: [u8; 64]) -> Self {
+        Self { bytes }
+    }
+}
+
+impl From<&PublicKey> for [u8; 64] {
+    fn from(k: &PublicKey) -> Self {
+        k.to_concated_array()
+    }
+}
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
     }
 }
-impl std::error::Error for PublicKeyFromConcatedHexStrError {}
 
 //
 //
 //
+impl From<&PublicKey> for AffinePoint {
+    fn from(k: &PublicKey) -> Self {
+        Self {
+            x: BigUint::from_bytes_be(k.x()),
+            y: BigUint::from_bytes_be(k.y()),
+        }
+    }
+}
+
+impl From<AffinePoint> for PublicKey {
+    fn from(p: AffinePoint) -> Self {
+        Self {
+            bytes: p.to_array(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl TryFrom<&PublicKey> for libsm::sm2::ecc::Point {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(k: &PublicKey) -> Result<Self, Self::Error> {
-        let curve = libsm::sm2::ecc::EccCtx::new();
-
-        curve
+        crate::context::ecc_ctx()
             .new_point(
-                &libsm::sm2::field::FieldElem::from_biguint(&k.x),
-                &libsm::sm2::field::FieldElem::from_biguint(&k.y),
+                &libsm::sm2::field::FieldElem::from_biguint(&BigUint::from_bytes_be(k.x())),
+                &libsm::sm2::field::FieldElem::from_biguint(&BigUint::from_bytes_be(k.y())),
             )
-            .map_err(|err| err.to_string())
+            .map_err(|_| Error::InvalidPublicKey)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&PublicKey> for gmsm::g2::subject::PublicKey {
     fn from(k: &PublicKey) -> Self {
         Self {
-            x: k.x.to_owned(),
-            y: k.y.to_owned(),
+            x: BigUint::from_bytes_be(k.x()),
+            y: BigUint::from_bytes_be(k.y()),
         }
     }
 }
 
-impl From<&gmsm::g2::subject::PublicKey> for PublicKey {
-    fn from(k: &gmsm::g2::subject::PublicKey) -> Self {
-        Self::new(k.x.to_owned(), k.y.to_owned())
+#[cfg(feature = "std")]
+impl TryFrom<&gmsm::g2::subject::PublicKey> for PublicKey {
+    type Error = Error;
+
+    fn try_from(k: &gmsm::g2::subject::PublicKey) -> Result<Self, Self::Error> {
+        Self::from_biguint(&k.x, &k.y)
     }
 }
 
@@ -115,39 +255,98 @@ impl From<&gmsm::g2::subject::PublicKey> for PublicKey {
 mod tests {
     use super::*;
 
-    use crate::{PUBLIC_KEY_X, PUBLIC_KEY_Y};
+    use crate::{types::PrivateKey, PUBLIC_KEY_X, PUBLIC_KEY_Y};
 
     #[test]
     fn test_from_concated_hex_str() {
         let public_key =
             PublicKey::from_concated_hex_str(format!("{}{}", PUBLIC_KEY_X, PUBLIC_KEY_Y).as_str())
                 .unwrap();
-        assert_eq!(
-            public_key.x,
-            BigUint::from_str_radix(PUBLIC_KEY_X, 16).unwrap()
-        );
-        assert_eq!(
-            public_key.y,
-            BigUint::from_str_radix(PUBLIC_KEY_Y, 16).unwrap()
-        );
+        assert_eq!(super::super::to_hex_str(public_key.x()), PUBLIC_KEY_X);
+        assert_eq!(super::super::to_hex_str(public_key.y()), PUBLIC_KEY_Y);
 
         //
         let public_key = PublicKey::from_concated_hex_str(
             format!("04{}{}", PUBLIC_KEY_X, PUBLIC_KEY_Y).as_str(),
         )
         .unwrap();
+        assert_eq!(super::super::to_hex_str(public_key.x()), PUBLIC_KEY_X);
+        assert_eq!(super::super::to_hex_str(public_key.y()), PUBLIC_KEY_Y);
+
+        // A multibyte character across the split point is an error, not a panic
+        for hex_str in [
+            format!("{}é{}", &PUBLIC_KEY_X[1..], &PUBLIC_KEY_Y[1..]),
+            format!("04{}é{}", &PUBLIC_KEY_X[1..], &PUBLIC_KEY_Y[1..]),
+        ] {
+            assert!(matches!(
+                PublicKey::from_concated_hex_str(&hex_str),
+                Err(Error::InvalidLength(127))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_from_str_and_try_from() {
+        let concated_hex_str = format!("{}{}", PUBLIC_KEY_X, PUBLIC_KEY_Y);
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        assert_eq!(concated_hex_str.parse::<PublicKey>().unwrap(), public_key);
+        assert_eq!(
+            format!("04{}", concated_hex_str)
+                .parse::<PublicKey>()
+                .unwrap(),
+            public_key
+        );
+        assert!("04".parse::<PublicKey>().is_err());
+
+        let bytes = public_key.to_concated_array();
+        assert_eq!(PublicKey::from(bytes), public_key);
+        assert_eq!(PublicKey::try_from(&bytes[..]).unwrap(), public_key);
+        let mut prefixed_bytes = vec![0x04];
+        prefixed_bytes.extend(bytes);
         assert_eq!(
-            public_key.x,
-            BigUint::from_str_radix(PUBLIC_KEY_X, 16).unwrap()
+            PublicKey::try_from(prefixed_bytes.as_slice()).unwrap(),
+            public_key
         );
+        prefixed_bytes[0] = 0x02;
+        assert!(PublicKey::try_from(prefixed_bytes.as_slice()).is_err());
+        assert!(PublicKey::try_from(&bytes[1..]).is_err());
+
+        assert_eq!(<[u8; 64]>::from(&public_key), bytes);
+    }
+
+    #[test]
+    fn test_eq_hash_and_ord() {
+        use std::collections::HashSet;
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+        let public_key_with_leading_zeros =
+            PublicKey::from_bytes(&[0; 32], public_key.y()).unwrap();
+        assert_eq!(
+            public_key_with_leading_zeros,
+            PublicKey::new([0; 32], public_key.y().to_owned())
+        );
+
+        let mut set = HashSet::new();
+        set.insert(public_key.clone());
+        set.insert(public_key.clone());
+        set.insert(public_key_with_leading_zeros.clone());
+        assert_eq!(set.len(), 2);
+
+        assert!(public_key_with_leading_zeros < public_key);
         assert_eq!(
-            public_key.y,
-            BigUint::from_str_radix(PUBLIC_KEY_Y, 16).unwrap()
+            public_key_with_leading_zeros.cmp(&public_key),
+            public_key_with_leading_zeros
+                .to_concated_array()
+                .cmp(&public_key.to_concated_array())
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_get_point_for_verify() {
+        use num_traits::Num as _;
+
         let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
         for _ in 1..=3 {
             let libsm::sm2::ecc::Point { x, y, z } =
@@ -170,6 +369,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate() {
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+        assert!(public_key.validate().is_ok());
+
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_Y, PUBLIC_KEY_X).unwrap();
+        assert!(matches!(
+            public_key.validate(),
+            Err(Error::InvalidPublicKey)
+        ));
+
+        let public_key = PublicKey::from_bytes(&[0xFF; 32], &[0xFF; 32]).unwrap();
+        assert!(matches!(
+            public_key.validate(),
+            Err(Error::InvalidPublicKey)
+        ));
+
+        assert!(matches!(
+            PublicKey::from_bytes(&[0xFF; 33], &[0xFF; 32]),
+            Err(Error::InvalidPublicKey)
+        ));
+    }
+
     #[test]
     fn test_to_concated_hex_str() {
         let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
@@ -184,4 +406,42 @@ mod tests {
         let public_key = PublicKey::from_concated_hex_str(PUBLIC_KEY).unwrap();
         assert_eq!(public_key.to_concated_hex_str(), PUBLIC_KEY);
     }
+
+    #[test]
+    fn test_der_and_pem() {
+        // openssl pkey -pubout
+        const PEM: &str = r#"-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoEcz1UBgi0DQgAE4JxxCrFzKwDeEXuAIFW5W4RKILjl
+PTFg5Q8wjkak3xJetkpFgwZO/f732LWTn4xmrFFt6WsYE2rz7EJ1hbokSg==
+-----END PUBLIC KEY-----
+"#;
+        let public_key = PrivateKey::from_hex_str(
+            "5359CF4B32455D90DD2DE4F1D00372610146D26AB716D4307942A0AE911D2CD7",
+        )
+        .unwrap()
+        .public_key();
+
+        assert_eq!(PublicKey::from_pem(PEM).unwrap(), public_key);
+        assert_eq!(public_key.to_pem(), PEM);
+        assert_eq!(
+            PublicKey::from_der(&public_key.to_der()).unwrap(),
+            public_key
+        );
+
+        let der = public_key.to_der();
+        for invalid in [&der[..der.len() - 1], &[der.as_slice(), &[0]].concat()] {
+            assert!(matches!(
+                PublicKey::from_der(invalid),
+                Err(Error::Malformed)
+            ));
+        }
+        // Parsing leaves the curve check to validate, as for the other formats
+        let mut off_curve = der.clone();
+        *off_curve.last_mut().unwrap() ^= 1;
+        assert!(PublicKey::from_der(&off_curve).unwrap().validate().is_err());
+        assert!(matches!(
+            PublicKey::from_pem(&PEM.replace("PUBLIC KEY", "PRIVATE KEY")),
+            Err(Error::Malformed)
+        ));
+    }
 }
@@ -1,18 +1,23 @@
-use core::fmt;
+use alloc::{string::String, vec::Vec};
+use core::{fmt, str::FromStr};
 
-use num_bigint::{BigUint, ParseBigIntError};
-use num_traits::Num as _;
+use num_bigint::BigUint;
 
-#[derive(Clone)]
+use crate::{
+    der::{self, Reader},
+    Error,
+};
+
+/// r || s, big-endian.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Signature {
-    pub r: BigUint,
-    pub s: BigUint,
+    bytes: [u8; 64],
 }
 impl fmt::Debug for Signature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Signature")
-            .field("r", &self.r.to_str_radix(16).to_uppercase())
-            .field("s", &self.s.to_str_radix(16).to_uppercase())
+            .field("r", &super::to_hex_str(self.r()))
+            .field("s", &super::to_hex_str(self.s()))
             .finish()
     }
 }
@@ -23,66 +28,178 @@ impl fmt::Display for Signature {
 }
 
 impl Signature {
-    pub fn new(r: BigUint, s: BigUint) -> Self {
-        Self { r, s }
+    /// Does not check the range of r and s, see `validate`.
+    pub fn new(r: [u8; 32], s: [u8; 32]) -> Self {
+        Self {
+            bytes: super::concat(&r, &s),
+        }
+    }
+
+    pub fn from_bytes(r_bytes: &[u8], s_bytes: &[u8]) -> Result<Self, Error> {
+        let signature = match (super::to_array(r_bytes), super::to_array(s_bytes)) {
+            (Some(r), Some(s)) => Self::new(r, s),
+            _ => return Err(Error::InvalidSignature),
+        };
+        signature.validate()?;
+        Ok(signature)
+    }
+
+    pub fn from_hex_str(r_hex_str: &str, s_hex_str: &str) -> Result<Self, Error> {
+        let signature = match (
+            super::hex_str_to_array(r_hex_str)?,
+            super::hex_str_to_array(s_hex_str)?,
+        ) {
+            (Some(r), Some(s)) => Self::new(r, s),
+            _ => return Err(Error::InvalidSignature),
+        };
+        signature.validate()?;
+        Ok(signature)
     }
 
-    pub fn from_bytes(r_bytes: &[u8], s_bytes: &[u8]) -> Self {
-        let r = BigUint::from_bytes_be(r_bytes);
-        let s = BigUint::from_bytes_be(s_bytes);
-        Self { r, s }
+    pub fn from_biguint(r: &BigUint, s: &BigUint) -> Result<Self, Error> {
+        Self::from_bytes(&r.to_bytes_be(), &s.to_bytes_be())
     }
 
-    pub fn from_hex_str(r_hex_str: &str, s_hex_str: &str) -> Result<Self, ParseBigIntError> {
-        let r = BigUint::from_str_radix(r_hex_str, 16)?;
-        let s = BigUint::from_str_radix(s_hex_str, 16)?;
-        Ok(Self { r, s })
+    /// Parses SEQUENCE { r INTEGER, s INTEGER }, the encoding of GB/T 35276-2017, OpenSSL
+    /// and BouncyCastle.
+    pub fn from_der(bytes: &[u8]) -> Result<Self, Error> {
+        let sequence = der::read_only(bytes, der::TAG_SEQUENCE).ok_or(Error::Malformed)?;
+        let mut reader = Reader::new(sequence);
+        match (
+            reader.read_unsigned_integer(),
+            reader.read_unsigned_integer(),
+        ) {
+            (Some(r), Some(s)) if reader.is_empty() => Self::from_bytes(r, s),
+            _ => Err(Error::Malformed),
+        }
     }
 
-    pub fn from_concated_hex_str(hex_str: &str) -> Result<Self, SignatureFromConcatedHexStrError> {
+    pub fn from_concated_hex_str(hex_str: &str) -> Result<Self, Error> {
         match hex_str.len() {
-            128 => Self::from_hex_str(&hex_str[..64], &hex_str[64..])
-                .map_err(SignatureFromConcatedHexStrError::ParseBigIntError),
-            _ => Err(SignatureFromConcatedHexStrError::Invalid),
+            128 => {
+                let (r, s) = super::split_hex_str(hex_str, 64)?;
+                Self::from_hex_str(r, s)
+            }
+            len => Err(Error::InvalidLength(len)),
+        }
+    }
+
+    pub fn from_concated_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        match bytes.len() {
+            64 => {
+                let mut array = [0; 64];
+                array.copy_from_slice(bytes);
+                let signature = Self { bytes: array };
+                signature.validate()?;
+                Ok(signature)
+            }
+            len => Err(Error::InvalidLength(len)),
         }
     }
 
+    /// Checks r and s are in [1, n-1].
+    pub fn validate(&self) -> Result<(), Error> {
+        // Big-endian arrays of equal length compare like the numbers they encode
+        let in_range = |v: &[u8; 32]| *v != [0; 32] && *v < crate::curve::N;
+        if !in_range(self.r()) || !in_range(self.s()) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    pub fn r(&self) -> &[u8; 32] {
+        super::first_half(&self.bytes)
+    }
+
+    pub fn s(&self) -> &[u8; 32] {
+        super::second_half(&self.bytes)
+    }
+
+    pub fn as_concated_array(&self) -> &[u8; 64] {
+        &self.bytes
+    }
+
+    pub fn to_concated_array(&self) -> [u8; 64] {
+        self.bytes
+    }
+
     pub fn to_concated_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(64);
-        bytes.extend(super::to_bytes::<32>(&self.r));
-        bytes.extend(super::to_bytes::<32>(&self.s));
-        bytes
+        self.bytes.to_vec()
     }
 
     pub fn to_concated_hex_str(&self) -> String {
-        super::to_hex_str(&self.to_concated_bytes())
+        super::to_hex_str(&self.bytes)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut sequence = Vec::new();
+        der::write_unsigned_integer(&mut sequence, self.r());
+        der::write_unsigned_integer(&mut sequence, self.s());
+        let mut out = Vec::new();
+        der::write(&mut out, der::TAG_SEQUENCE, &sequence);
+        out
     }
 }
 
-#[derive(Debug)]
-pub enum SignatureFromConcatedHexStrError {
-    Invalid,
-    ParseBigIntError(ParseBigIntError),
+//
+//
+//
+impl FromStr for Signature {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_concated_hex_str(s)
+    }
 }
-impl fmt::Display for SignatureFromConcatedHexStrError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_concated_bytes(bytes)
+    }
+}
+
+impl TryFrom<[u8; 64]> for Signature {
+    type Error = Error;
+
+    fn try_from(bytes: [u8; 64]) -> Result<Self, Self::Error> {
+        Self::from_concated_bytes(&bytes)
+    }
+}
+
+impl From<&Signature> for [u8; 64] {
+    fn from(s: &Signature) -> Self {
+        s.to_concated_array()
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
     }
 }
-impl std::error::Error for SignatureFromConcatedHexStrError {}
 
 //
 //
 //
-impl From<&Signature> for libsm::sm2::signature::Signature {
-    fn from(s: &Signature) -> Self {
-        Self::new(s.r.to_bytes_be().as_ref(), s.s.to_bytes_be().as_ref())
+#[cfg(feature = "std")]
+impl TryFrom<&Signature> for libsm::sm2::signature::Signature {
+    type Error = Error;
+
+    fn try_from(s: &Signature) -> Result<Self, Self::Error> {
+        s.validate()?;
+
+        Ok(Self::new(s.r(), s.s()))
     }
 }
 
-impl From<&libsm::sm2::signature::Signature> for Signature {
-    fn from(s: &libsm::sm2::signature::Signature) -> Self {
-        Self::new(s.get_r().to_owned(), s.get_s().to_owned())
+#[cfg(feature = "std")]
+impl TryFrom<&libsm::sm2::signature::Signature> for Signature {
+    type Error = Error;
+
+    fn try_from(s: &libsm::sm2::signature::Signature) -> Result<Self, Self::Error> {
+        Self::from_biguint(s.get_r(), s.get_s())
     }
 }
 
@@ -90,8 +207,9 @@ impl From<&libsm::sm2::signature::Signature> for Signature {
 mod tests {
     use super::*;
 
-    use crate::{PrivateKey, PUBLIC_KEY_X, PUBLIC_KEY_Y};
+    use crate::{PUBLIC_KEY_X, PUBLIC_KEY_Y};
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_to_concated_hex_str() {
         let signature = Signature::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
@@ -103,7 +221,7 @@ mod tests {
         );
 
         let sk = "EE198D2262508EDC3A96DA1C1BE646EAF24911B31A0749AC5E6BC31EF501A052";
-        let id = "ID12341234567890";
+        let id = b"ID12341234567890".as_slice();
         let data = [
             22, 7, 7, 12, 15, 38, 0, 214, 2, 22, 7, 7, 12, 15, 38, 0, 0, 0, 125, 125, 0, 0, 0, 0,
             15, 160, 15, 160, 0, 0, 0, 34, 32, 34, 32, 0, 0, 40, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -115,9 +233,118 @@ mod tests {
             0, 0, 3, 0, 0, 0, 4,
         ];
         let err_signature =  "29AA22C223E560C5C39870FC62ADB0C163BB26CF2D4DDA6B43C1C0C7E603E9715372B0F8E0257C1EFCF90C71C11FB7CE86EA271D76B6C70E02B6471446151C";
-        let sk = PrivateKey::from_hex_str(sk).unwrap();
-        let signature = crate::sign(&sk, data, Some(id));
+        let sk = crate::PrivateKey::from_hex_str(sk).unwrap();
+        let signature = crate::sign(&sk, data, Some(id)).unwrap();
         assert_ne!(signature.to_concated_hex_str(), err_signature);
         assert_eq!(signature.to_concated_bytes().len(), 64);
     }
+
+    #[test]
+    fn test_from_str_and_try_from() {
+        let concated_hex_str = format!("{}{}", PUBLIC_KEY_X, PUBLIC_KEY_Y);
+        let signature = Signature::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+
+        assert_eq!(concated_hex_str.parse::<Signature>().unwrap(), signature);
+        assert!(format!("04{}", concated_hex_str)
+            .parse::<Signature>()
+            .is_err());
+        assert!(matches!(
+            format!("{}é{}", &PUBLIC_KEY_X[1..], &PUBLIC_KEY_Y[1..]).parse::<Signature>(),
+            Err(Error::InvalidLength(127))
+        ));
+
+        let bytes = signature.to_concated_array();
+        assert_eq!(Signature::try_from(bytes).unwrap(), signature);
+        assert_eq!(Signature::try_from(&bytes[..]).unwrap(), signature);
+        assert!(Signature::try_from(&bytes[1..]).is_err());
+
+        assert_eq!(<[u8; 64]>::from(&signature), bytes);
+    }
+
+    #[test]
+    fn test_validate() {
+        let n = crate::curve::N;
+        let n_1 = super::super::to_bytes::<32>(&(crate::curve::n() - 1_u8));
+        let mut one = [0; 32];
+        one[31] = 1;
+
+        assert!(Signature::new(one, n_1).validate().is_ok());
+        for (r, s) in [([0; 32], one), (one, [0; 32]), (n, one), (one, n)] {
+            let signature = Signature::new(r, s);
+            assert!(matches!(signature.validate(), Err(Error::InvalidSignature)));
+            assert!(matches!(
+                Signature::from_concated_hex_str(&signature.to_concated_hex_str()),
+                Err(Error::InvalidSignature)
+            ));
+            assert!(matches!(
+                Signature::try_from(signature.to_concated_array()),
+                Err(Error::InvalidSignature)
+            ));
+        }
+
+        assert!(matches!(
+            Signature::from_hex_str(&"F".repeat(66), "01"),
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_eq_hash_and_ord() {
+        use std::collections::BTreeSet;
+
+        let signature_1 = Signature::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+        let signature_2 = Signature::from_hex_str(PUBLIC_KEY_Y, PUBLIC_KEY_X).unwrap();
+        assert_ne!(signature_1, signature_2);
+
+        let set = vec![
+            signature_1.clone(),
+            signature_2.clone(),
+            signature_1.clone(),
+        ]
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+        assert_eq!(
+            set.into_iter().collect::<Vec<_>>(),
+            vec![signature_2, signature_1]
+        );
+    }
+
+    #[test]
+    fn test_der() {
+        // openssl pkeyutl -sign over "TEST" with the key of PrivateKey::test_der_and_pem. OpenSSL 3
+        // signs with an empty user id unless distid is set.
+        const DER: &str = "304402204c2d0cf76338c605e61dd7543b90b32755c20a6a8df195773114b9640f63311e022062f0e63a412b0581870214dad02050e8bd98beb8a4de46f4cfb1ac16012cebc8";
+        const DER_WITH_USER_ID: &str = "3045022100e55ce78f34483f2efdb328a97566bcffd1071f4593811dcc3a1e50436fb2420c022027a99e93f1286b48e4036ab649b3bd0342f1c45ec710f73c209587e0bb4fe10e";
+        let public_key = crate::types::PrivateKey::from_hex_str(
+            "5359CF4B32455D90DD2DE4F1D00372610146D26AB716D4307942A0AE911D2CD7",
+        )
+        .unwrap()
+        .public_key();
+
+        for (der, user_id) in [
+            (DER, Some(b"".as_slice())),
+            (DER_WITH_USER_ID, Some(b"ALICE123@YAHOO.COM".as_slice())),
+        ] {
+            let der = hex_simd::decode_to_boxed_bytes(der.as_bytes()).unwrap();
+            let signature = Signature::from_der(&der).unwrap();
+            assert_eq!(*signature.to_der(), *der);
+            assert!(crate::verify(&public_key, b"TEST", user_id, &signature).unwrap());
+
+            assert!(matches!(
+                Signature::from_der(&der[..der.len() - 1]),
+                Err(Error::Malformed)
+            ));
+        }
+
+        // r = 0 is out of range
+        assert!(Signature::from_der(&[0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x01]).is_err());
+        // Small r and s round-trip in their shortest form
+        let signature =
+            Signature::from_der(&[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x7F]).unwrap();
+        assert_eq!(signature.r()[31], 1);
+        assert_eq!(
+            signature.to_der(),
+            [0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x7F]
+        );
+    }
 }
@@ -0,0 +1,128 @@
+use core::fmt;
+
+use crate::{
+    backend::{
+        arith::ProjectivePoint,
+        vartime::{self, KeyTable},
+    },
+    hash, Error,
+};
+
+use super::{PublicKey, Signature};
+
+/// A validated public key with the multiples used by verification precomputed, for checking
+/// many signatures against the same key.
+#[derive(Clone)]
+pub struct VerifyingKey {
+    public_key: PublicKey,
+    table: KeyTable,
+    /// Z_A for the default user id.
+    z_a: [u8; 32],
+}
+impl fmt::Debug for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifyingKey")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+impl VerifyingKey {
+    pub fn new(public_key: &PublicKey) -> Result<Self, Error> {
+        public_key.validate()?;
+
+        let point = ProjectivePoint::from_affine(public_key.as_concated_array());
+        Ok(Self {
+            public_key: public_key.clone(),
+            table: KeyTable::new(&point),
+            z_a: hash::z_a(hash::DEFAULT_USER_ID, public_key.as_concated_array()),
+        })
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Same as `crate::verify` with this key.
+    pub fn verify<'a>(
+        &self,
+        msg: impl AsRef<[u8]>,
+        user_id: impl Into<Option<&'a [u8]>>,
+        signature: &Signature,
+    ) -> Result<bool, Error> {
+        let user_id = user_id.into();
+
+        crate::check_user_id(user_id)?;
+        signature.validate()?;
+
+        let e = match user_id {
+            Some(user_id) => hash::e(user_id, self.public_key.as_concated_array(), msg.as_ref()),
+            None => hash::e_with_z_a(&self.z_a, msg.as_ref()),
+        };
+        Ok(vartime::verify(
+            crate::context::g_table(),
+            &self.table,
+            &e,
+            signature.as_concated_array(),
+        ))
+    }
+}
+
+impl TryFrom<&PublicKey> for VerifyingKey {
+    type Error = Error;
+
+    fn try_from(public_key: &PublicKey) -> Result<Self, Self::Error> {
+        Self::new(public_key)
+    }
+}
+
+impl From<VerifyingKey> for PublicKey {
+    fn from(key: VerifyingKey) -> Self {
+        key.public_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng as _};
+
+    use crate::{types::PrivateKey, PRIVATE_KEY, PUBLIC_KEY_X, PUBLIC_KEY_Y};
+
+    #[test]
+    fn test_verify() {
+        let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+        let public_key = PublicKey::from_hex_str(PUBLIC_KEY_X, PUBLIC_KEY_Y).unwrap();
+        let key = VerifyingKey::new(&public_key).unwrap();
+        assert_eq!(key.public_key(), &public_key);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for user_id in [
+            None,
+            Some(b"ALICE123@YAHOO.COM".as_slice()),
+            Some(b"".as_slice()),
+        ] {
+            for msg in ["", "TEST", "message digest"] {
+                let signature = crate::sign_with_rng(&mut rng, &private_key, msg, user_id).unwrap();
+                assert!(key.verify(msg, user_id, &signature).unwrap());
+                assert!(!key.verify("OTHER", user_id, &signature).unwrap());
+
+                let tampered = Signature::new(*signature.r(), crate::curve::N_MINUS_1);
+                assert_eq!(
+                    key.verify(msg, user_id, &tampered).unwrap(),
+                    crate::verify_via_soft(&public_key, msg, user_id, &tampered).unwrap()
+                );
+            }
+        }
+
+        assert!(matches!(
+            key.verify("TEST", None, &Signature::new([0; 32], [1; 32])),
+            Err(Error::InvalidSignature)
+        ));
+        assert!(matches!(
+            VerifyingKey::new(&PublicKey::new([1; 32], [2; 32])),
+            Err(Error::InvalidPublicKey)
+        ));
+    }
+}
@@ -0,0 +1,36 @@
+use std::{env, path::Path, process::Command};
+
+const TARGET: &str = "thumbv7em-none-eabihf";
+
+#[test]
+fn test_build_without_std() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let output = Command::new(rustc)
+        .args(["--print", "target-libdir", "--target", TARGET])
+        .output()
+        .unwrap();
+    let target_libdir = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        output.status.success() && Path::new(target_libdir.trim()).exists(),
+        "{} is not installed, run `rustup target add {}`",
+        TARGET,
+        TARGET
+    );
+
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    let status = Command::new(cargo)
+        .args([
+            "build",
+            "--lib",
+            "--no-default-features",
+            "--target",
+            TARGET,
+        ])
+        .arg("--manifest-path")
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("no_std"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
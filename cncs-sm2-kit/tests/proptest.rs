@@ -0,0 +1,229 @@
+//! Key formats, sign / verify and encrypt / decrypt on random input, and rejection of any
+//! flipped bit. The format properties run 256 cases, raise them with `PROPTEST_CASES`, the
+//! signing / encryption ones 64.
+
+use cncs_sm2_kit::{
+    decrypt, decrypt_auto, encrypt_with_rng, sign_with_rng,
+    types::{
+        Ciphertext, CiphertextEncoding, DecodeOptions, EncodeOptions, EncryptMode, PrivateKey,
+        PublicKey, Signature, VerifyingKey,
+    },
+    verify, verify_strict,
+};
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng as _};
+
+/// Random keys, plus small ones whose hex and byte forms start with zeros.
+fn private_key() -> impl Strategy<Value = PrivateKey> {
+    prop_oneof![
+        any::<u64>().prop_map(|seed| PrivateKey::random(&mut StdRng::seed_from_u64(seed))),
+        (1_u64..).prop_map(|d| {
+            let mut bytes = [0; 32];
+            bytes[24..].copy_from_slice(&d.to_be_bytes());
+            PrivateKey::new(bytes)
+        }),
+    ]
+}
+
+/// 32 bytes, starting with up to 32 zeros.
+fn coordinate() -> impl Strategy<Value = [u8; 32]> {
+    (any::<[u8; 32]>(), 0_usize..=32).prop_map(|(mut bytes, zeros)| {
+        bytes[..zeros].fill(0);
+        bytes
+    })
+}
+
+// Z_A hashes bytes, so the user id need not be UTF-8
+fn user_id() -> impl Strategy<Value = Option<Vec<u8>>> {
+    proptest::option::of(proptest::collection::vec(any::<u8>(), 0..=40))
+}
+
+fn mode() -> impl Strategy<Value = EncryptMode> {
+    prop_oneof![Just(EncryptMode::C1C2C3), Just(EncryptMode::C1C3C2)]
+}
+
+fn encoding() -> impl Strategy<Value = CiphertextEncoding> {
+    prop_oneof![
+        Just(CiphertextEncoding::Binary),
+        Just(CiphertextEncoding::UpperHex),
+        Just(CiphertextEncoding::LowerHex),
+        Just(CiphertextEncoding::Base64),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn test_private_key_round_trips(private_key in private_key()) {
+        prop_assert!(private_key.validate().is_ok());
+
+        let hex_str = private_key.to_hex_str();
+        prop_assert_eq!(hex_str.len(), 64);
+        prop_assert_eq!(&PrivateKey::from_hex_str(&hex_str).unwrap(), &private_key);
+        prop_assert_eq!(&hex_str.parse::<PrivateKey>().unwrap(), &private_key);
+        prop_assert_eq!(&hex_str.to_lowercase().parse::<PrivateKey>().unwrap(), &private_key);
+
+        let bytes = private_key.to_bytes();
+        prop_assert_eq!(&PrivateKey::from_bytes(&bytes).unwrap(), &private_key);
+        prop_assert_eq!(&PrivateKey::try_from(&bytes[..]).unwrap(), &private_key);
+        prop_assert_eq!(&PrivateKey::from(private_key.to_array()), &private_key);
+        prop_assert_eq!(
+            &PrivateKey::from_biguint(&private_key.to_biguint()).unwrap(),
+            &private_key
+        );
+    }
+
+    #[test]
+    fn test_public_key_round_trips(private_key in private_key()) {
+        let public_key = private_key.public_key();
+        prop_assert!(public_key.validate().is_ok());
+        prop_assert_eq!(&private_key.public_key_via_soft(), &public_key);
+
+        let hex_str = public_key.to_concated_hex_str();
+        prop_assert_eq!(&PublicKey::from_concated_hex_str(&hex_str).unwrap(), &public_key);
+        prop_assert_eq!(
+            &PublicKey::from_concated_hex_str(&format!("04{}", hex_str)).unwrap(),
+            &public_key
+        );
+        prop_assert_eq!(&hex_str.parse::<PublicKey>().unwrap(), &public_key);
+        prop_assert_eq!(
+            &PublicKey::from_hex_str(&hex_str[..64], &hex_str[64..]).unwrap(),
+            &public_key
+        );
+
+        let bytes = public_key.to_concated_bytes();
+        prop_assert_eq!(&PublicKey::from_concated_bytes(&bytes).unwrap(), &public_key);
+        prop_assert_eq!(
+            &PublicKey::from_concated_bytes(&[&[0x04][..], &bytes].concat()).unwrap(),
+            &public_key
+        );
+        prop_assert_eq!(&PublicKey::try_from(&bytes[..]).unwrap(), &public_key);
+        prop_assert_eq!(
+            &PublicKey::from_bytes(&bytes[..32], &bytes[32..]).unwrap(),
+            &public_key
+        );
+        prop_assert_eq!(&PublicKey::from(public_key.to_concated_array()), &public_key);
+        let verifying_key = VerifyingKey::new(&public_key).unwrap();
+        prop_assert_eq!(verifying_key.public_key(), &public_key);
+    }
+
+    #[test]
+    fn test_concated_hex_str_len(x in coordinate(), y in coordinate()) {
+        let public_key = PublicKey::new(x, y);
+        let hex_str = public_key.to_concated_hex_str();
+        prop_assert_eq!(hex_str.len(), 128);
+        prop_assert_eq!(&PublicKey::from_concated_hex_str(&hex_str).unwrap(), &public_key);
+        prop_assert_eq!(public_key.to_concated_bytes().len(), 64);
+
+        let signature = Signature::new(x, y);
+        let hex_str = signature.to_concated_hex_str();
+        prop_assert_eq!(hex_str.len(), 128);
+        if signature.validate().is_ok() {
+            prop_assert_eq!(&Signature::from_concated_hex_str(&hex_str).unwrap(), &signature);
+            prop_assert_eq!(
+                &Signature::from_concated_bytes(&signature.to_concated_bytes()).unwrap(),
+                &signature
+            );
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_sign_and_verify(
+        private_key in private_key(),
+        msg in proptest::collection::vec(any::<u8>(), 0..256),
+        user_id in user_id(),
+        seed in any::<u64>(),
+    ) {
+        let public_key = private_key.public_key();
+        let user_id = user_id.as_deref();
+        let signature =
+            sign_with_rng(&mut StdRng::seed_from_u64(seed), &private_key, &msg, user_id).unwrap();
+
+        prop_assert!(verify(&public_key, &msg, user_id, &signature).unwrap());
+        prop_assert!(
+            verify_strict(&public_key, &msg, user_id, signature.as_concated_array()).is_ok()
+        );
+        let verifying_key = VerifyingKey::new(&public_key).unwrap();
+        prop_assert!(verifying_key.verify(&msg, user_id, &signature).unwrap());
+
+        let bytes = signature.to_concated_array();
+        prop_assert_eq!(&Signature::try_from(bytes).unwrap(), &signature);
+        prop_assert_eq!(
+            &signature.to_concated_hex_str().parse::<Signature>().unwrap(),
+            &signature
+        );
+    }
+
+    #[test]
+    fn test_tampered_signature_fails(
+        private_key in private_key(),
+        msg in proptest::collection::vec(any::<u8>(), 0..64),
+        user_id in user_id(),
+        seed in any::<u64>(),
+        bit in 0_usize..512,
+    ) {
+        let public_key = private_key.public_key();
+        let user_id = user_id.as_deref();
+        let signature =
+            sign_with_rng(&mut StdRng::seed_from_u64(seed), &private_key, &msg, user_id).unwrap();
+
+        let mut bytes = signature.to_concated_array();
+        bytes[bit / 8] ^= 0x80 >> (bit % 8);
+        // Out of range is an error, in range a mismatch
+        if let Ok(tampered) = Signature::try_from(bytes) {
+            prop_assert!(!verify(&public_key, &msg, user_id, &tampered).unwrap());
+        }
+        prop_assert!(verify_strict(&public_key, &msg, user_id, bytes).is_err());
+
+        let mut other = msg.clone();
+        other.push(0);
+        prop_assert!(!verify(&public_key, &other, user_id, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt(
+        private_key in private_key(),
+        msg in proptest::collection::vec(any::<u8>(), 0..512),
+        mode in mode(),
+        encoding in encoding(),
+        prefix in any::<bool>(),
+        seed in any::<u64>(),
+    ) {
+        let public_key = private_key.public_key();
+        let ciphertext =
+            encrypt_with_rng(&mut StdRng::seed_from_u64(seed), &public_key, &msg, mode).unwrap();
+        prop_assert_eq!(ciphertext.len(), cncs_sm2_kit::ciphertext_len(msg.len()));
+        prop_assert_eq!(&decrypt(&private_key, &ciphertext, mode).unwrap(), &msg);
+        prop_assert_eq!(&decrypt_auto(&private_key, &ciphertext).unwrap().0, &msg);
+
+        let parsed = Ciphertext::from_bytes(&ciphertext, mode).unwrap();
+        prop_assert_eq!(&Ciphertext::from_der(&parsed.to_der()).unwrap(), &parsed);
+        let encoded = parsed.encode(&EncodeOptions { mode, encoding, prefix });
+        for prefix in [Some(prefix), None] {
+            let options = DecodeOptions { mode, encoding, prefix };
+            prop_assert_eq!(&Ciphertext::decode(&encoded, &options).unwrap(), &parsed);
+        }
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_fails(
+        private_key in private_key(),
+        msg in proptest::collection::vec(any::<u8>(), 0..64),
+        mode in mode(),
+        seed in any::<u64>(),
+        bit in any::<prop::sample::Index>(),
+    ) {
+        let public_key = private_key.public_key();
+        let mut ciphertext =
+            encrypt_with_rng(&mut StdRng::seed_from_u64(seed), &public_key, &msg, mode).unwrap();
+
+        let bit = bit.index(ciphertext.len() * 8);
+        ciphertext[bit / 8] ^= 0x80 >> (bit % 8);
+        prop_assert!(decrypt(&private_key, &ciphertext, mode).is_err());
+
+        prop_assert!(decrypt_auto(&private_key, &ciphertext).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+// Decrypts every vector in vectors.txt with sm-crypto, then prints a fresh
+// sm-crypto ciphertext of the same plaintext for ../sm_crypto.rs to decrypt.
+//
+// npm install && node check.js
+
+const fs = require('fs');
+const path = require('path');
+const { sm2 } = require('sm-crypto');
+
+const PRIVATE_KEY = '7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D';
+const PUBLIC_KEY =
+  '04FE1FC819D6A8827DB65BF1E114713CE68F116DAA3D6A75A3D8A5E48FAD68C591' +
+  '7F925FA394747EC86613C62EF4FD77E97BD8FF9744453CD4728CFE37F955183A';
+
+// sm-crypto numbers the layouts the other way round from gmsm
+const CIPHER_MODES = { C1C2C3: 0, C1C3C2: 1 };
+
+const vectors = fs
+  .readFileSync(path.join(__dirname, 'vectors.txt'), 'utf8')
+  .split('\n')
+  .filter((line) => line && !line.startsWith('#'))
+  .map((line) => line.split(' '));
+
+for (const [mode, msg, ciphertext] of vectors) {
+  const plaintext = sm2.doDecrypt(ciphertext, PRIVATE_KEY, CIPHER_MODES[mode], { output: 'array' });
+  if (Buffer.from(plaintext).toString('hex') !== msg) {
+    console.error(`sm-crypto failed to decrypt ${mode} ${msg}`);
+    process.exit(1);
+  }
+
+  const fresh = sm2.doEncrypt(Array.from(Buffer.from(msg, 'hex')), PUBLIC_KEY, CIPHER_MODES[mode]);
+  console.log(`${mode} ${msg} ${fresh}`);
+}
@@ -0,0 +1,24 @@
+// Prints sm-crypto doEncrypt ciphertexts in both cipherModes for vectors.txt.
+//
+// npm install && node generate.js >> vectors.txt
+
+const { sm2 } = require('sm-crypto');
+
+const PUBLIC_KEY =
+  '04FE1FC819D6A8827DB65BF1E114713CE68F116DAA3D6A75A3D8A5E48FAD68C591' +
+  '7F925FA394747EC86613C62EF4FD77E97BD8FF9744453CD4728CFE37F955183A';
+
+// sm-crypto numbers the layouts the other way round from gmsm
+const CIPHER_MODES = { C1C2C3: 0, C1C3C2: 1 };
+
+// Around the 32-byte KDF block boundaries
+const MSG_LENS = [1, 4, 31, 32, 33, 64, 100, 1000];
+
+console.log(`# From sm-crypto ${require('sm-crypto/package.json').version} doEncrypt`);
+for (const len of MSG_LENS) {
+  const msg = Buffer.alloc(len, 0).map((_, i) => i);
+  for (const [mode, cipherMode] of Object.entries(CIPHER_MODES)) {
+    const ciphertext = sm2.doEncrypt(Array.from(msg), PUBLIC_KEY, cipherMode);
+    console.log(`${mode} ${msg.toString('hex')} ${ciphertext}`);
+  }
+}
@@ -0,0 +1,107 @@
+//! Ciphertexts in the sm-crypto wire format, see sm-crypto/vectors.txt.
+
+use std::{path::Path, process::Command};
+
+use cncs_sm2_kit::{
+    decrypt_with_options,
+    types::{Ciphertext, DecodeOptions, EncodeOptions, EncryptMode, PrivateKey},
+};
+
+const PRIVATE_KEY: &str = "7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D";
+
+const VECTORS: &str = include_str!("sm-crypto/vectors.txt");
+
+fn parse(vectors: &str) -> Vec<(EncryptMode, Vec<u8>, String)> {
+    vectors
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.split(' ');
+            let mode = match parts.next().unwrap() {
+                "C1C2C3" => EncryptMode::C1C2C3,
+                "C1C3C2" => EncryptMode::C1C3C2,
+                mode => panic!("unknown mode {}", mode),
+            };
+            let msg = hex_simd::decode_to_boxed_bytes(parts.next().unwrap().as_bytes()).unwrap();
+            (mode, msg.into(), parts.next().unwrap().to_owned())
+        })
+        .collect()
+}
+
+#[test]
+fn test_vectors() {
+    let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+
+    for (mode, msg, ciphertext) in parse(VECTORS) {
+        let options = DecodeOptions {
+            mode,
+            ..DecodeOptions::SM_CRYPTO
+        };
+        assert_eq!(
+            decrypt_with_options(&private_key, &ciphertext, &options).unwrap(),
+            msg
+        );
+        assert_eq!(
+            decrypt_with_options(
+                &private_key,
+                &ciphertext,
+                &DecodeOptions {
+                    prefix: None,
+                    ..options
+                }
+            )
+            .unwrap(),
+            msg
+        );
+
+        let encoded = Ciphertext::decode(&ciphertext, &options)
+            .unwrap()
+            .encode(&EncodeOptions {
+                mode,
+                ..EncodeOptions::SM_CRYPTO
+            });
+        assert_eq!(encoded, ciphertext.as_bytes());
+    }
+}
+
+#[test]
+#[ignore = "needs node and `npm install` in tests/sm-crypto"]
+fn test_sm_crypto_round_trip() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/sm-crypto");
+    assert!(
+        dir.join("node_modules/sm-crypto").exists(),
+        "run `npm install` in {}",
+        dir.display()
+    );
+
+    let output = Command::new("node")
+        .arg(dir.join("check.js"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Otherwise test_vectors only covers what this crate and gmsm wrote
+    assert!(
+        VECTORS.contains("# From sm-crypto 0.3.13"),
+        "run `node generate.js >> vectors.txt` in {} and commit it with package-lock.json",
+        dir.display()
+    );
+
+    let private_key = PrivateKey::from_hex_str(PRIVATE_KEY).unwrap();
+    let fresh = parse(&String::from_utf8(output.stdout).unwrap());
+    assert_eq!(fresh.len(), parse(VECTORS).len());
+    for (mode, msg, ciphertext) in fresh {
+        let options = DecodeOptions {
+            mode,
+            ..DecodeOptions::SM_CRYPTO
+        };
+        assert_eq!(
+            decrypt_with_options(&private_key, &ciphertext, &options).unwrap(),
+            msg
+        );
+    }
+}
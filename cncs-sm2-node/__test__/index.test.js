@@ -0,0 +1,134 @@
+'use strict'
+
+const assert = require('node:assert/strict')
+const fs = require('node:fs')
+const path = require('node:path')
+const test = require('node:test')
+
+const sm2 = require('..')
+
+function readVectors() {
+  const file = path.join(__dirname, '..', '..', 'fixtures', 'vectors.txt')
+  const vectors = {}
+  for (const line of fs.readFileSync(file, 'utf8').split('\n')) {
+    if (line && !line.startsWith('#')) {
+      const space = line.indexOf(' ')
+      vectors[line.slice(0, space)] = line.slice(space + 1).replaceAll('\\n', '\n')
+    }
+  }
+  return vectors
+}
+
+// The vectors of test.php in cncs-sm2-php and of OpenSSL
+const VECTORS = readVectors()
+const PRIVATE_KEY = VECTORS.PRIVATE_KEY
+const PUBLIC_KEY = VECTORS.PUBLIC_KEY
+const SIGNATURE = Buffer.from(VECTORS.SIGNATURE, 'hex')
+const SIGNATURE_WITH_USER_ID = Buffer.from(VECTORS.SIGNATURE_WITH_USER_ID, 'hex')
+const CIPHERTEXT_C1C3C2 = Buffer.from(VECTORS.CIPHERTEXT_C1C3C2, 'hex')
+const CIPHERTEXT_C1C2C3 = Buffer.from(VECTORS.CIPHERTEXT_C1C2C3, 'hex')
+
+const OPENSSL_PRIVATE_KEY = VECTORS.OPENSSL_PRIVATE_KEY
+const OPENSSL_PRIVATE_KEY_PEM = VECTORS.OPENSSL_PRIVATE_KEY_PEM
+const OPENSSL_PUBLIC_KEY_PEM = VECTORS.OPENSSL_PUBLIC_KEY_PEM
+// With an empty user id
+const OPENSSL_SIGNATURE_DER = Buffer.from(VECTORS.OPENSSL_SIGNATURE_DER, 'hex')
+
+const MSG = Buffer.from('TEST')
+
+test('verify', () => {
+  assert.ok(sm2.verify(PUBLIC_KEY, MSG, SIGNATURE))
+  assert.ok(sm2.verify(PUBLIC_KEY, MSG, SIGNATURE, null))
+  assert.ok(sm2.verify(PUBLIC_KEY, MSG, SIGNATURE_WITH_USER_ID, '1234567812345678'))
+  assert.ok(sm2.verify(`04${PUBLIC_KEY.toLowerCase()}`, MSG, SIGNATURE))
+
+  assert.ok(!sm2.verify(PUBLIC_KEY, Buffer.from('TEST2'), SIGNATURE))
+  assert.ok(!sm2.verify(PUBLIC_KEY, MSG, SIGNATURE, 'ALICE123@YAHOO.COM'))
+})
+
+test('sign', () => {
+  for (const userId of [undefined, '1234567812345678', 'ALICE123@YAHOO.COM', Buffer.from([0xff, 0x00])]) {
+    const signature = sm2.sign(PRIVATE_KEY, MSG, userId)
+    assert.equal(signature.length, 64)
+    assert.ok(sm2.verify(PUBLIC_KEY, MSG, signature, userId))
+    assert.deepEqual(sm2.signatureFromDer(sm2.signatureToDer(signature)), signature)
+  }
+})
+
+test('decrypt', () => {
+  assert.equal(sm2.decrypt(PRIVATE_KEY, CIPHERTEXT_C1C3C2).toString(), 'TEST')
+  assert.equal(
+    sm2.decrypt(PRIVATE_KEY, CIPHERTEXT_C1C2C3, { mode: sm2.EncryptMode.C1C2C3 }).toString(),
+    'TEST',
+  )
+
+  // sm-crypto leaves out the 04 prefix
+  const withoutPrefix = CIPHERTEXT_C1C3C2.subarray(1)
+  assert.equal(sm2.decrypt(PRIVATE_KEY, withoutPrefix).toString(), 'TEST')
+  assert.equal(sm2.decrypt(PRIVATE_KEY, withoutPrefix, { prefix: false }).toString(), 'TEST')
+
+  assert.throws(() => sm2.decrypt(PRIVATE_KEY, CIPHERTEXT_C1C2C3), { code: 'GenericFailure' })
+})
+
+test('encrypt', () => {
+  const msg = Buffer.from(Array.from({ length: 256 }, (_, i) => i))
+  for (const mode of [undefined, sm2.EncryptMode.C1C3C2, sm2.EncryptMode.C1C2C3]) {
+    const ciphertext = sm2.encrypt(PUBLIC_KEY, msg, { mode })
+    assert.equal(ciphertext.length, 1 + 64 + 32 + msg.length)
+    assert.equal(ciphertext[0], 0x04)
+    assert.deepEqual(sm2.decrypt(PRIVATE_KEY, ciphertext, { mode }), msg)
+
+    const withoutPrefix = sm2.encrypt(PUBLIC_KEY, msg, { mode, prefix: false })
+    assert.equal(withoutPrefix.length, 64 + 32 + msg.length)
+    assert.deepEqual(sm2.decrypt(PRIVATE_KEY, withoutPrefix, { mode, prefix: false }), msg)
+  }
+})
+
+test('keys', () => {
+  assert.equal(sm2.getPublicKey(PRIVATE_KEY), PUBLIC_KEY)
+
+  const { privateKey, publicKey } = sm2.generateKeyPair()
+  assert.equal(privateKey.length, 64)
+  assert.equal(sm2.getPublicKey(privateKey), publicKey)
+  assert.equal(sm2.privateKeyFromPem(sm2.privateKeyToPem(privateKey)), privateKey)
+  assert.equal(sm2.publicKeyFromPem(sm2.publicKeyToPem(publicKey)), publicKey)
+
+  assert.equal(sm2.privateKeyFromPem(OPENSSL_PRIVATE_KEY_PEM), OPENSSL_PRIVATE_KEY)
+  assert.equal(sm2.privateKeyToPem(OPENSSL_PRIVATE_KEY), OPENSSL_PRIVATE_KEY_PEM)
+  assert.equal(sm2.publicKeyFromPem(OPENSSL_PUBLIC_KEY_PEM), sm2.getPublicKey(OPENSSL_PRIVATE_KEY))
+
+  const signature = sm2.signatureFromDer(OPENSSL_SIGNATURE_DER)
+  assert.deepEqual(sm2.signatureToDer(signature), OPENSSL_SIGNATURE_DER)
+  assert.ok(sm2.verify(sm2.publicKeyFromPem(OPENSSL_PUBLIC_KEY_PEM), MSG, signature, ''))
+})
+
+test('errors', () => {
+  const invalidArg = { code: 'InvalidArg' }
+  assert.throws(() => sm2.getPublicKey('XYZ'), invalidArg)
+  assert.throws(() => sm2.getPublicKey('00'), invalidArg)
+  assert.throws(() => sm2.publicKeyToPem(PUBLIC_KEY.slice(0, -1) + 'B'), invalidArg)
+  assert.throws(() => sm2.publicKeyFromPem(OPENSSL_PRIVATE_KEY_PEM), invalidArg)
+  assert.throws(() => sm2.verify(PUBLIC_KEY, MSG, SIGNATURE.subarray(1)), invalidArg)
+  assert.throws(() => sm2.verify(PUBLIC_KEY, MSG, Buffer.alloc(64)), invalidArg)
+  assert.throws(() => sm2.sign(PRIVATE_KEY, MSG, 'A'.repeat(8192)), invalidArg)
+  assert.throws(() => sm2.signatureFromDer(Buffer.from([0x30, 0x00])), invalidArg)
+})
+
+test('async', async () => {
+  const signature = await sm2.signAsync(PRIVATE_KEY, MSG, 'ALICE123@YAHOO.COM')
+  assert.ok(await sm2.verifyAsync(PUBLIC_KEY, MSG, signature, 'ALICE123@YAHOO.COM'))
+  assert.ok(!(await sm2.verifyAsync(PUBLIC_KEY, MSG, signature)))
+
+  const ciphertexts = await Promise.all(
+    Array.from({ length: 8 }, () => sm2.encryptAsync(PUBLIC_KEY, MSG, { prefix: false })),
+  )
+  for (const ciphertext of ciphertexts) {
+    assert.deepEqual(await sm2.decryptAsync(PRIVATE_KEY, ciphertext), MSG)
+  }
+
+  // Argument errors reject before reaching the threadpool, decryption errors after
+  await assert.rejects(async () => sm2.signAsync('XYZ', MSG), { code: 'InvalidArg' })
+  await assert.rejects(sm2.decryptAsync(PRIVATE_KEY, CIPHERTEXT_C1C2C3), {
+    code: 'GenericFailure',
+  })
+})
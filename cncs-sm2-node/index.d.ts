@@ -0,0 +1,82 @@
+/* Keep in sync with the #[napi] items of src/lib.rs */
+
+/** `EncryptMode` of cncs-sm2-kit. */
+export declare enum EncryptMode {
+  C1C3C2 = 0,
+  C1C2C3 = 1,
+}
+
+export interface EncryptOptions {
+  /** Defaults to C1C3C2. */
+  mode?: EncryptMode
+  /** Whether C1 starts with `04`, defaults to true. sm-crypto omits it. */
+  prefix?: boolean
+}
+
+export interface DecryptOptions {
+  /** Defaults to C1C3C2. */
+  mode?: EncryptMode
+  /** Whether C1 starts with `04`, detected when absent. */
+  prefix?: boolean
+}
+
+/** Hex keys: 64 characters for the private key, 128 (x || y) for the public key. */
+export interface KeyPair {
+  privateKey: string
+  publicKey: string
+}
+
+export declare function generateKeyPair(): KeyPair
+/** The public key of a hex private key, as 128 hex characters. */
+export declare function getPublicKey(privateKey: string): string
+/** PKCS#8, or SEC1 `EC PRIVATE KEY` / `SM2 PRIVATE KEY`. */
+export declare function privateKeyFromPem(pem: string): string
+export declare function privateKeyToPem(privateKey: string): string
+export declare function publicKeyFromPem(pem: string): string
+/** Accepts x || y with or without the `04` prefix. */
+export declare function publicKeyToPem(publicKey: string): string
+
+/** 64-byte r || s to ASN.1 DER. */
+export declare function signatureToDer(signature: Buffer): Buffer
+export declare function signatureFromDer(der: Buffer): Buffer
+
+/** Returns r || s. `userId` defaults to 1234567812345678. */
+export declare function sign(privateKey: string, msg: Buffer, userId?: string | Buffer | undefined | null): Buffer
+export declare function signAsync(
+  privateKey: string,
+  msg: Buffer,
+  userId?: string | Buffer | undefined | null,
+): Promise<Buffer>
+export declare function verify(
+  publicKey: string,
+  msg: Buffer,
+  signature: Buffer,
+  userId?: string | Buffer | undefined | null,
+): boolean
+export declare function verifyAsync(
+  publicKey: string,
+  msg: Buffer,
+  signature: Buffer,
+  userId?: string | Buffer | undefined | null,
+): Promise<boolean>
+
+export declare function encrypt(
+  publicKey: string,
+  msg: Buffer,
+  options?: EncryptOptions | undefined | null,
+): Buffer
+export declare function encryptAsync(
+  publicKey: string,
+  msg: Buffer,
+  options?: EncryptOptions | undefined | null,
+): Promise<Buffer>
+export declare function decrypt(
+  privateKey: string,
+  ciphertext: Buffer,
+  options?: DecryptOptions | undefined | null,
+): Buffer
+export declare function decryptAsync(
+  privateKey: string,
+  ciphertext: Buffer,
+  options?: DecryptOptions | undefined | null,
+): Promise<Buffer>
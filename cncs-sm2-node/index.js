@@ -0,0 +1,3 @@
+'use strict'
+
+module.exports = require('./cncs_sm2.node')
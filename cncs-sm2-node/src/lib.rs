@@ -0,0 +1,380 @@
+use cncs_sm2_kit::{
+    decrypt_with_options, encrypt_with_options, sign as kit_sign,
+    types::{
+        CiphertextEncoding, DecodeOptions, EncodeOptions, EncryptMode as KitEncryptMode,
+        PrivateKey, PublicKey, Signature,
+    },
+    verify as kit_verify, Error,
+};
+use napi::{bindgen_prelude::*, Env, Task};
+use napi_derive::napi;
+
+fn to_napi_err(err: Error) -> napi::Error {
+    let status = match err {
+        Error::InvalidCiphertext => Status::GenericFailure,
+        _ => Status::InvalidArg,
+    };
+    napi::Error::new(status, err.to_string())
+}
+
+/// A string as UTF-8, or any bytes.
+fn user_id_bytes(user_id: Either<String, Buffer>) -> Vec<u8> {
+    match user_id {
+        Either::A(user_id) => user_id.into_bytes(),
+        Either::B(user_id) => user_id.to_vec(),
+    }
+}
+
+fn parse_private_key(private_key: &str) -> Result<PrivateKey> {
+    let private_key = PrivateKey::from_hex_str(private_key).map_err(to_napi_err)?;
+    private_key.validate().map_err(to_napi_err)?;
+    Ok(private_key)
+}
+
+fn parse_public_key(public_key: &str) -> Result<PublicKey> {
+    let public_key = PublicKey::from_concated_hex_str(public_key).map_err(to_napi_err)?;
+    public_key.validate().map_err(to_napi_err)?;
+    Ok(public_key)
+}
+
+//
+//
+//
+/// `EncryptMode` of cncs-sm2-kit.
+#[napi]
+pub enum EncryptMode {
+    C1C3C2 = 0,
+    C1C2C3 = 1,
+}
+
+impl From<EncryptMode> for KitEncryptMode {
+    fn from(mode: EncryptMode) -> Self {
+        match mode {
+            EncryptMode::C1C3C2 => KitEncryptMode::C1C3C2,
+            EncryptMode::C1C2C3 => KitEncryptMode::C1C2C3,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct EncryptOptions {
+    /// Defaults to C1C3C2.
+    pub mode: Option<EncryptMode>,
+    /// Whether C1 starts with `04`, defaults to true. sm-crypto omits it.
+    pub prefix: Option<bool>,
+}
+
+impl From<EncryptOptions> for EncodeOptions {
+    fn from(options: EncryptOptions) -> Self {
+        EncodeOptions {
+            mode: options.mode.map(Into::into).unwrap_or_default(),
+            encoding: CiphertextEncoding::Binary,
+            prefix: options.prefix.unwrap_or(true),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct DecryptOptions {
+    /// Defaults to C1C3C2.
+    pub mode: Option<EncryptMode>,
+    /// Whether C1 starts with `04`, detected when absent.
+    pub prefix: Option<bool>,
+}
+
+impl From<DecryptOptions> for DecodeOptions {
+    fn from(options: DecryptOptions) -> Self {
+        DecodeOptions {
+            mode: options.mode.map(Into::into).unwrap_or_default(),
+            encoding: CiphertextEncoding::Binary,
+            prefix: options.prefix,
+        }
+    }
+}
+
+//
+// Keys, as hex strings
+//
+#[napi(object)]
+pub struct KeyPair {
+    pub private_key: String,
+    pub public_key: String,
+}
+
+#[napi]
+pub fn generate_key_pair() -> KeyPair {
+    let private_key = PrivateKey::random(&mut rand_core::OsRng);
+    KeyPair {
+        public_key: private_key.public_key().to_concated_hex_str(),
+        private_key: private_key.to_hex_str(),
+    }
+}
+
+#[napi]
+pub fn get_public_key(private_key: String) -> Result<String> {
+    Ok(parse_private_key(&private_key)?
+        .public_key()
+        .to_concated_hex_str())
+}
+
+/// PKCS#8, or SEC1 `EC PRIVATE KEY` / `SM2 PRIVATE KEY`.
+#[napi]
+pub fn private_key_from_pem(pem: String) -> Result<String> {
+    let private_key = PrivateKey::from_pem(&pem).map_err(to_napi_err)?;
+    private_key.validate().map_err(to_napi_err)?;
+    Ok(private_key.to_hex_str())
+}
+
+#[napi]
+pub fn private_key_to_pem(private_key: String) -> Result<String> {
+    Ok(parse_private_key(&private_key)?.to_pem())
+}
+
+#[napi]
+pub fn public_key_from_pem(pem: String) -> Result<String> {
+    let public_key = PublicKey::from_pem(&pem).map_err(to_napi_err)?;
+    public_key.validate().map_err(to_napi_err)?;
+    Ok(public_key.to_concated_hex_str())
+}
+
+#[napi]
+pub fn public_key_to_pem(public_key: String) -> Result<String> {
+    Ok(parse_public_key(&public_key)?.to_pem())
+}
+
+//
+// Signatures, as 64-byte r || s
+//
+#[napi]
+pub fn signature_to_der(signature: Buffer) -> Result<Buffer> {
+    let signature = Signature::from_concated_bytes(&signature).map_err(to_napi_err)?;
+    Ok(signature.to_der().into())
+}
+
+#[napi]
+pub fn signature_from_der(der: Buffer) -> Result<Buffer> {
+    let signature = Signature::from_der(&der).map_err(to_napi_err)?;
+    Ok(signature.to_concated_bytes().into())
+}
+
+pub struct Sign {
+    private_key: PrivateKey,
+    msg: Vec<u8>,
+    user_id: Option<Vec<u8>>,
+}
+
+impl Sign {
+    fn new(
+        private_key: String,
+        msg: Buffer,
+        user_id: Option<Either<String, Buffer>>,
+    ) -> Result<Self> {
+        Ok(Self {
+            private_key: parse_private_key(&private_key)?,
+            msg: msg.to_vec(),
+            user_id: user_id.map(user_id_bytes),
+        })
+    }
+}
+
+impl Task for Sign {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let signature =
+            kit_sign(&self.private_key, &self.msg, self.user_id.as_deref()).map_err(to_napi_err)?;
+        Ok(signature.to_concated_bytes())
+    }
+
+    fn resolve(&mut self, _: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+#[napi]
+pub fn sign(
+    private_key: String,
+    msg: Buffer,
+    user_id: Option<Either<String, Buffer>>,
+) -> Result<Buffer> {
+    let mut task = Sign::new(private_key, msg, user_id)?;
+    task.compute().map(Into::into)
+}
+
+#[napi]
+pub fn sign_async(
+    private_key: String,
+    msg: Buffer,
+    user_id: Option<Either<String, Buffer>>,
+) -> Result<AsyncTask<Sign>> {
+    Ok(AsyncTask::new(Sign::new(private_key, msg, user_id)?))
+}
+
+pub struct Verify {
+    public_key: PublicKey,
+    msg: Vec<u8>,
+    signature: Signature,
+    user_id: Option<Vec<u8>>,
+}
+
+impl Verify {
+    fn new(
+        public_key: String,
+        msg: Buffer,
+        signature: Buffer,
+        user_id: Option<Either<String, Buffer>>,
+    ) -> Result<Self> {
+        Ok(Self {
+            public_key: parse_public_key(&public_key)?,
+            msg: msg.to_vec(),
+            signature: Signature::from_concated_bytes(&signature).map_err(to_napi_err)?,
+            user_id: user_id.map(user_id_bytes),
+        })
+    }
+}
+
+impl Task for Verify {
+    type Output = bool;
+    type JsValue = bool;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        kit_verify(
+            &self.public_key,
+            &self.msg,
+            self.user_id.as_deref(),
+            &self.signature,
+        )
+        .map_err(to_napi_err)
+    }
+
+    fn resolve(&mut self, _: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+#[napi]
+pub fn verify(
+    public_key: String,
+    msg: Buffer,
+    signature: Buffer,
+    user_id: Option<Either<String, Buffer>>,
+) -> Result<bool> {
+    Verify::new(public_key, msg, signature, user_id)?.compute()
+}
+
+#[napi]
+pub fn verify_async(
+    public_key: String,
+    msg: Buffer,
+    signature: Buffer,
+    user_id: Option<Either<String, Buffer>>,
+) -> Result<AsyncTask<Verify>> {
+    Ok(AsyncTask::new(Verify::new(
+        public_key, msg, signature, user_id,
+    )?))
+}
+
+//
+// Ciphertexts, as bytes
+//
+pub struct Encrypt {
+    public_key: PublicKey,
+    msg: Vec<u8>,
+    options: EncodeOptions,
+}
+
+impl Encrypt {
+    fn new(public_key: String, msg: Buffer, options: Option<EncryptOptions>) -> Result<Self> {
+        Ok(Self {
+            public_key: parse_public_key(&public_key)?,
+            msg: msg.to_vec(),
+            options: options.map(Into::into).unwrap_or_default(),
+        })
+    }
+}
+
+impl Task for Encrypt {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        encrypt_with_options(&self.public_key, &self.msg, &self.options).map_err(to_napi_err)
+    }
+
+    fn resolve(&mut self, _: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+#[napi]
+pub fn encrypt(public_key: String, msg: Buffer, options: Option<EncryptOptions>) -> Result<Buffer> {
+    let mut task = Encrypt::new(public_key, msg, options)?;
+    task.compute().map(Into::into)
+}
+
+#[napi]
+pub fn encrypt_async(
+    public_key: String,
+    msg: Buffer,
+    options: Option<EncryptOptions>,
+) -> Result<AsyncTask<Encrypt>> {
+    Ok(AsyncTask::new(Encrypt::new(public_key, msg, options)?))
+}
+
+pub struct Decrypt {
+    private_key: PrivateKey,
+    ciphertext: Vec<u8>,
+    options: DecodeOptions,
+}
+
+impl Decrypt {
+    fn new(
+        private_key: String,
+        ciphertext: Buffer,
+        options: Option<DecryptOptions>,
+    ) -> Result<Self> {
+        Ok(Self {
+            private_key: parse_private_key(&private_key)?,
+            ciphertext: ciphertext.to_vec(),
+            options: options.map(Into::into).unwrap_or_default(),
+        })
+    }
+}
+
+impl Task for Decrypt {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        decrypt_with_options(&self.private_key, &self.ciphertext, &self.options)
+            .map_err(to_napi_err)
+    }
+
+    fn resolve(&mut self, _: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+#[napi]
+pub fn decrypt(
+    private_key: String,
+    ciphertext: Buffer,
+    options: Option<DecryptOptions>,
+) -> Result<Buffer> {
+    let mut task = Decrypt::new(private_key, ciphertext, options)?;
+    task.compute().map(Into::into)
+}
+
+#[napi]
+pub fn decrypt_async(
+    private_key: String,
+    ciphertext: Buffer,
+    options: Option<DecryptOptions>,
+) -> Result<AsyncTask<Decrypt>> {
+    Ok(AsyncTask::new(Decrypt::new(
+        private_key,
+        ciphertext,
+        options,
+    )?))
+}
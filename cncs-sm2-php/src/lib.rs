@@ -3,14 +3,18 @@ use cncs_sm2_kit::{
     types::{EncryptMode, PrivateKey, PublicKey, Signature},
     verify,
 };
-use ext_php_rs::prelude::{php_function, php_module, ModuleBuilder, PhpResult};
+use ext_php_rs::{
+    binary::Binary,
+    prelude::{php_class, php_function, php_impl, php_module, ModuleBuilder, PhpResult},
+};
 
 #[php_function]
 pub fn sm2_sign(private_key_hex: &str, msg: &str, user_id: Option<&str>) -> PhpResult<String> {
     let private_key = PrivateKey::from_hex_str(private_key_hex)
         .map_err(|err| format!("Parse private_key_hex failed, err: {}", err))?;
 
-    let signature = sign(&private_key, msg, user_id);
+    let signature = sign(&private_key, msg, user_id.map(str::as_bytes))
+        .map_err(|err| format!("Sign failed, err: {}", err))?;
 
     Ok(signature.to_concated_hex_str())
 }
@@ -28,7 +32,7 @@ pub fn sm2_verify(
     let signature = Signature::from_concated_hex_str(signature_hex)
         .map_err(|err| format!("Parse signature_hex failed, err: {}", err))?;
 
-    let ret = verify(&public_key, msg, user_id, &signature)
+    let ret = verify(&public_key, msg, user_id.map(str::as_bytes), &signature)
         .map_err(|err| format!("Verify failed, err: {}", err))?;
 
     Ok(ret)
@@ -45,7 +49,8 @@ pub fn sm2_encrypt(public_key_hex: &str, msg: &str, mode: Option<u8>) -> PhpResu
         None
     };
 
-    let msg_encrypted = encrypt(&public_key, msg, mode);
+    let msg_encrypted =
+        encrypt(&public_key, msg, mode).map_err(|err| format!("Encrypt failed, err: {}", err))?;
     let msg_encrypted_base64 = base64::encode(&msg_encrypted);
 
     Ok(msg_encrypted_base64)
@@ -69,7 +74,8 @@ pub fn sm2_decrypt(
     let msg_encrypted = base64::decode(msg_encrypted_base64)
         .map_err(|err| format!("Parse msg_encrypted_base64 failed, err: {}", err))?;
 
-    let msg = decrypt(&private_key, msg_encrypted, mode);
+    let msg = decrypt(&private_key, msg_encrypted, mode)
+        .map_err(|err| format!("Decrypt failed, err: {}", err))?;
 
     let msg_string = String::from_utf8(msg)
         .map_err(|err| format!("Convert msg to string failed, err: {}", err))?;
@@ -77,6 +83,198 @@ pub fn sm2_decrypt(
     Ok(msg_string)
 }
 
+//
+//
+//
+#[php_class]
+pub struct Sm2PrivateKey(PrivateKey);
+
+#[php_impl]
+impl Sm2PrivateKey {
+    pub fn from_hex(hex: &str) -> PhpResult<Self> {
+        let private_key = PrivateKey::from_hex_str(hex)
+            .map_err(|err| format!("Parse hex failed, err: {}", err))?;
+
+        validated_private_key(private_key)
+    }
+
+    pub fn from_der(der: Binary<u8>) -> PhpResult<Self> {
+        let private_key =
+            PrivateKey::from_der(&der).map_err(|err| format!("Parse der failed, err: {}", err))?;
+
+        validated_private_key(private_key)
+    }
+
+    pub fn from_pem(pem: &str) -> PhpResult<Self> {
+        let private_key =
+            PrivateKey::from_pem(pem).map_err(|err| format!("Parse pem failed, err: {}", err))?;
+
+        validated_private_key(private_key)
+    }
+
+    pub fn generate() -> Self {
+        Self(PrivateKey::random(&mut rand_core::OsRng))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.to_hex_str()
+    }
+
+    pub fn to_der(&self) -> Binary<u8> {
+        self.0.to_der().into()
+    }
+
+    pub fn to_pem(&self) -> String {
+        self.0.to_pem()
+    }
+
+    pub fn public_key(&self) -> Sm2PublicKey {
+        Sm2PublicKey(self.0.public_key())
+    }
+
+    #[optional(user_id)]
+    pub fn sign(&self, msg: &str, user_id: Option<&str>) -> PhpResult<Sm2Signature> {
+        let signature = sign(&self.0, msg, user_id.map(str::as_bytes))
+            .map_err(|err| format!("Sign failed, err: {}", err))?;
+
+        Ok(Sm2Signature(signature))
+    }
+
+    #[optional(mode)]
+    pub fn decrypt(&self, msg_encrypted_base64: &str, mode: Option<u8>) -> PhpResult<String> {
+        let mode = if let Some(mode) = mode {
+            Some(prase_encrypt_mode(mode)?)
+        } else {
+            None
+        };
+
+        let msg_encrypted = base64::decode(msg_encrypted_base64)
+            .map_err(|err| format!("Parse msg_encrypted_base64 failed, err: {}", err))?;
+
+        let msg = decrypt(&self.0, msg_encrypted, mode)
+            .map_err(|err| format!("Decrypt failed, err: {}", err))?;
+
+        let msg_string = String::from_utf8(msg)
+            .map_err(|err| format!("Convert msg to string failed, err: {}", err))?;
+
+        Ok(msg_string)
+    }
+}
+
+#[php_class]
+pub struct Sm2PublicKey(PublicKey);
+
+#[php_impl]
+impl Sm2PublicKey {
+    pub fn from_hex(hex: &str) -> PhpResult<Self> {
+        let public_key = PublicKey::from_concated_hex_str(hex)
+            .map_err(|err| format!("Parse hex failed, err: {}", err))?;
+
+        validated_public_key(public_key)
+    }
+
+    pub fn from_der(der: Binary<u8>) -> PhpResult<Self> {
+        let public_key =
+            PublicKey::from_der(&der).map_err(|err| format!("Parse der failed, err: {}", err))?;
+
+        validated_public_key(public_key)
+    }
+
+    pub fn from_pem(pem: &str) -> PhpResult<Self> {
+        let public_key =
+            PublicKey::from_pem(pem).map_err(|err| format!("Parse pem failed, err: {}", err))?;
+
+        validated_public_key(public_key)
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.to_concated_hex_str()
+    }
+
+    pub fn to_der(&self) -> Binary<u8> {
+        self.0.to_der().into()
+    }
+
+    pub fn to_pem(&self) -> String {
+        self.0.to_pem()
+    }
+
+    #[optional(user_id)]
+    pub fn verify(
+        &self,
+        msg: &str,
+        signature: &Sm2Signature,
+        user_id: Option<&str>,
+    ) -> PhpResult<bool> {
+        let ret = verify(&self.0, msg, user_id.map(str::as_bytes), &signature.0)
+            .map_err(|err| format!("Verify failed, err: {}", err))?;
+
+        Ok(ret)
+    }
+
+    #[optional(mode)]
+    pub fn encrypt(&self, msg: &str, mode: Option<u8>) -> PhpResult<String> {
+        let mode = if let Some(mode) = mode {
+            Some(prase_encrypt_mode(mode)?)
+        } else {
+            None
+        };
+
+        let msg_encrypted =
+            encrypt(&self.0, msg, mode).map_err(|err| format!("Encrypt failed, err: {}", err))?;
+        let msg_encrypted_base64 = base64::encode(&msg_encrypted);
+
+        Ok(msg_encrypted_base64)
+    }
+}
+
+#[php_class]
+pub struct Sm2Signature(Signature);
+
+#[php_impl]
+impl Sm2Signature {
+    pub fn from_hex(hex: &str) -> PhpResult<Self> {
+        let signature = Signature::from_concated_hex_str(hex)
+            .map_err(|err| format!("Parse hex failed, err: {}", err))?;
+
+        Ok(Self(signature))
+    }
+
+    pub fn from_der(der: Binary<u8>) -> PhpResult<Self> {
+        let signature =
+            Signature::from_der(&der).map_err(|err| format!("Parse der failed, err: {}", err))?;
+
+        Ok(Self(signature))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.to_concated_hex_str()
+    }
+
+    pub fn to_der(&self) -> Binary<u8> {
+        self.0.to_der().into()
+    }
+}
+
+//
+//
+//
+fn validated_private_key(private_key: PrivateKey) -> PhpResult<Sm2PrivateKey> {
+    private_key
+        .validate()
+        .map_err(|err| format!("Validate private_key failed, err: {}", err))?;
+
+    Ok(Sm2PrivateKey(private_key))
+}
+
+fn validated_public_key(public_key: PublicKey) -> PhpResult<Sm2PublicKey> {
+    public_key
+        .validate()
+        .map_err(|err| format!("Validate public_key failed, err: {}", err))?;
+
+    Ok(Sm2PublicKey(public_key))
+}
+
 fn prase_encrypt_mode(mode: u8) -> Result<EncryptMode, String> {
     match mode {
         0 => Ok(EncryptMode::C1C3C2),
@@ -1,16 +1,63 @@
 <?php
 
-define("PRIVATE_KEY", "7D2B2391F9633469156F700F8B00D9C85EB6B5327B68684483742EC4AC43043D");
-define("PUBLIC_KEY", "FE1FC819D6A8827DB65BF1E114713CE68F116DAA3D6A75A3D8A5E48FAD68C5917F925FA394747EC86613C62EF4FD77E97BD8FF9744453CD4728CFE37F955183A");
+// The vectors shared with the tests of the other bindings
+$vectors = [];
+foreach (file(__DIR__ . "/../fixtures/vectors.txt", FILE_IGNORE_NEW_LINES) as $line) {
+    if ($line !== "" && $line[0] !== "#") {
+        [$name, $value] = explode(" ", $line, 2);
+        $vectors[$name] = str_replace('\n', "\n", $value);
+    }
+}
+
+define("PRIVATE_KEY", $vectors["PRIVATE_KEY"]);
+define("PUBLIC_KEY", $vectors["PUBLIC_KEY"]);
+define("SIGNATURE", $vectors["SIGNATURE"]);
+define("SIGNATURE_WITH_USER_ID", $vectors["SIGNATURE_WITH_USER_ID"]);
+define("CIPHERTEXT_C1C3C2", base64_encode(hex2bin($vectors["CIPHERTEXT_C1C3C2"])));
+define("CIPHERTEXT_C1C2C3", base64_encode(hex2bin($vectors["CIPHERTEXT_C1C2C3"])));
 
 var_dump(sm2_sign(PRIVATE_KEY, "TEST", null));
 var_dump(sm2_sign(PRIVATE_KEY, "TEST", "1234567812345678"));
 
-assert(sm2_verify(PUBLIC_KEY, "TEST", null, "16262FB1C2887A5634DD47960BFE00AEC52ADD78D6A3762D89F17FE9B7ED93CD11789337D03256B8A79D71ABA80B121E2F832BE196E71CD55D4109E9F6011E32"));
-assert(sm2_verify(PUBLIC_KEY, "TEST", "1234567812345678", "7AC28527C6B049FF74C42DE1541DB6BA34FEFE843A65BDAD49292B2830A85100202004346D91D896EF8B363EF42AD787D80F8815FA08FF2C5C204B083DF308F3"));
+assert(sm2_verify(PUBLIC_KEY, "TEST", null, SIGNATURE));
+assert(sm2_verify(PUBLIC_KEY, "TEST", "1234567812345678", SIGNATURE_WITH_USER_ID));
 
 var_dump(sm2_encrypt(PUBLIC_KEY, "TEST", null));
 var_dump(sm2_encrypt(PUBLIC_KEY, "TEST", 1));
 
-assert(sm2_decrypt(PRIVATE_KEY, "BIRtJo5HLttC60UjPZITjhPsNU9H04tWrOXlBLqppWRWRCV+1DnL3GWAnIrRc7/iW7SjHbv4UW6zlRWejcw5kkGhlpVM7y510vyh9MOBat1ssVo4MluxyHuNLawY+OMgetGHJP4=", null) == "TEST");
-assert(sm2_decrypt(PRIVATE_KEY, "BPEjTYzruv4ReHl80Jaqu+lHBPNYVZmWwp4zOc1MCbj4LwRcO5Pb/n9V0fs5Aa/4sCOgzEkBq8On2060Spe+x8PuJLhs3kO4Ow0C1pqfTZwD4OIGvcMQgGHyzl5n8+UQA5gknuU=", 1) == "TEST");
+assert(sm2_decrypt(PRIVATE_KEY, CIPHERTEXT_C1C3C2, null) == "TEST");
+assert(sm2_decrypt(PRIVATE_KEY, CIPHERTEXT_C1C2C3, 1) == "TEST");
+
+$private_key = Sm2PrivateKey::fromHex(PRIVATE_KEY);
+$public_key = Sm2PublicKey::fromHex(PUBLIC_KEY);
+assert($private_key->toHex() == PRIVATE_KEY);
+assert($private_key->publicKey()->toHex() == PUBLIC_KEY);
+assert(Sm2PrivateKey::fromPem($private_key->toPem())->toHex() == PRIVATE_KEY);
+assert(Sm2PublicKey::fromPem($public_key->toPem())->toHex() == PUBLIC_KEY);
+assert(Sm2PublicKey::fromDer($public_key->toDer())->toHex() == PUBLIC_KEY);
+
+assert($public_key->verify("TEST", Sm2Signature::fromHex(SIGNATURE)));
+assert($public_key->verify("TEST", Sm2Signature::fromHex(SIGNATURE_WITH_USER_ID), "1234567812345678"));
+
+$signature = $private_key->sign("TEST");
+assert($public_key->verify("TEST", $signature));
+assert($public_key->verify("TEST", Sm2Signature::fromDer($signature->toDer())));
+assert($public_key->verify("TEST", $signature, "1234567812345678"));
+assert(!$public_key->verify("TEST", $signature, "ALICE123@YAHOO.COM"));
+
+assert($private_key->decrypt(CIPHERTEXT_C1C3C2) == "TEST");
+assert($private_key->decrypt($public_key->encrypt("TEST", 1), 1) == "TEST");
+
+foreach ([
+    fn() => Sm2PrivateKey::fromHex(str_repeat("0", 64)),
+    fn() => Sm2PublicKey::fromHex(str_repeat("0", 128)),
+] as $f) {
+    try {
+        $f();
+        assert(false);
+    } catch (Exception $e) {
+    }
+}
+
+$generated = Sm2PrivateKey::generate();
+assert($generated->decrypt($generated->publicKey()->encrypt("TEST")) == "TEST");
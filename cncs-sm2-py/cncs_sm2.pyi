@@ -0,0 +1,71 @@
+from typing import Optional, Union
+
+_Data = Union[bytes, str]
+_Mode = Union["EncryptMode", int, None]
+
+class Sm2Error(ValueError): ...
+class ParseError(Sm2Error): ...
+class InvalidKeyError(Sm2Error): ...
+class InvalidSignatureError(Sm2Error): ...
+class InvalidUserIdError(Sm2Error): ...
+class DecryptionError(Sm2Error): ...
+
+class EncryptMode:
+    C1C3C2: EncryptMode
+    C1C2C3: EncryptMode
+    def __int__(self) -> int: ...
+
+class PrivateKey:
+    @staticmethod
+    def generate() -> PrivateKey: ...
+    @staticmethod
+    def from_hex(hex: str) -> PrivateKey: ...
+    @staticmethod
+    def from_bytes(bytes: bytes) -> PrivateKey: ...
+    @staticmethod
+    def from_der(der: bytes) -> PrivateKey: ...
+    @staticmethod
+    def from_pem(pem: str) -> PrivateKey: ...
+    def to_hex(self) -> str: ...
+    def to_bytes(self) -> bytes: ...
+    def to_der(self) -> bytes: ...
+    def to_pem(self) -> str: ...
+    def public_key(self) -> PublicKey: ...
+    def sign(self, msg: _Data, user_id: Optional[_Data] = None) -> Signature: ...
+    def decrypt(self, ciphertext: bytes, mode: _Mode = None) -> bytes: ...
+
+class PublicKey:
+    @staticmethod
+    def from_hex(hex: str) -> PublicKey: ...
+    @staticmethod
+    def from_bytes(bytes: bytes) -> PublicKey: ...
+    @staticmethod
+    def from_der(der: bytes) -> PublicKey: ...
+    @staticmethod
+    def from_pem(pem: str) -> PublicKey: ...
+    def to_hex(self) -> str: ...
+    def to_bytes(self) -> bytes: ...
+    def to_der(self) -> bytes: ...
+    def to_pem(self) -> str: ...
+    def verify(
+        self, msg: _Data, signature: Signature, user_id: Optional[_Data] = None
+    ) -> bool: ...
+    def encrypt(self, msg: _Data, mode: _Mode = None) -> bytes: ...
+
+class Signature:
+    @staticmethod
+    def from_hex(hex: str) -> Signature: ...
+    @staticmethod
+    def from_bytes(bytes: bytes) -> Signature: ...
+    @staticmethod
+    def from_der(der: bytes) -> Signature: ...
+    def to_hex(self) -> str: ...
+    def to_bytes(self) -> bytes: ...
+    def to_der(self) -> bytes: ...
+
+def sign(private_key_hex: str, msg: _Data, user_id: Optional[_Data] = None) -> str: ...
+def verify(
+    public_key_hex: str, msg: _Data, user_id: Optional[_Data], signature_hex: str
+) -> bool: ...
+def encrypt(public_key_hex: str, msg: _Data, mode: _Mode = None) -> str: ...
+def decrypt(private_key_hex: str, msg_encrypted_base64: str, mode: _Mode = None) -> bytes: ...
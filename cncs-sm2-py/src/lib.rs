@@ -0,0 +1,480 @@
+use cncs_sm2_kit::{
+    decrypt as kit_decrypt, encrypt as kit_encrypt, sign as kit_sign,
+    types::{
+        EncryptMode as KitEncryptMode, PrivateKey as KitPrivateKey, PublicKey as KitPublicKey,
+        Signature as KitSignature,
+    },
+    verify as kit_verify, Error,
+};
+use pyo3::{
+    create_exception,
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyBytes, PyString},
+};
+
+create_exception!(
+    cncs_sm2,
+    Sm2Error,
+    PyValueError,
+    "Base class of every SM2 error."
+);
+create_exception!(
+    cncs_sm2,
+    ParseError,
+    Sm2Error,
+    "Malformed hex, base64, DER or PEM."
+);
+create_exception!(
+    cncs_sm2,
+    InvalidKeyError,
+    Sm2Error,
+    "Key out of range or off the curve."
+);
+create_exception!(
+    cncs_sm2,
+    InvalidSignatureError,
+    Sm2Error,
+    "Signature r or s out of range."
+);
+create_exception!(
+    cncs_sm2,
+    InvalidUserIdError,
+    Sm2Error,
+    "User id too long or not UTF-8."
+);
+create_exception!(
+    cncs_sm2,
+    DecryptionError,
+    Sm2Error,
+    "Ciphertext malformed or tampered."
+);
+
+fn to_py_err(err: Error) -> PyErr {
+    let msg = err.to_string();
+    match err {
+        Error::ParseHex(_) | Error::InvalidLength(_) | Error::InvalidPrefix | Error::Malformed => {
+            ParseError::new_err(msg)
+        }
+        Error::InvalidPrivateKey | Error::InvalidPublicKey => InvalidKeyError::new_err(msg),
+        Error::InvalidSignature => InvalidSignatureError::new_err(msg),
+        Error::InvalidUserId => InvalidUserIdError::new_err(msg),
+        Error::InvalidCiphertext => DecryptionError::new_err(msg),
+        _ => Sm2Error::new_err(msg),
+    }
+}
+
+//
+//
+//
+/// bytes, or str as UTF-8.
+fn extract_bytes<'py>(obj: &Bound<'py, PyAny>, name: &str) -> PyResult<Vec<u8>> {
+    if let Ok(bytes) = obj.cast::<PyBytes>() {
+        Ok(bytes.as_bytes().to_vec())
+    } else if let Ok(string) = obj.cast::<PyString>() {
+        Ok(string.to_cow()?.as_bytes().to_vec())
+    } else {
+        Err(pyo3::exceptions::PyTypeError::new_err(format!(
+            "{} must be bytes or str",
+            name
+        )))
+    }
+}
+
+fn extract_user_id(obj: Option<&Bound<'_, PyAny>>) -> PyResult<Option<Vec<u8>>> {
+    match obj {
+        None => Ok(None),
+        Some(obj) if obj.is_none() => Ok(None),
+        Some(obj) => extract_bytes(obj, "user_id").map(Some),
+    }
+}
+
+fn extract_mode(obj: Option<&Bound<'_, PyAny>>) -> PyResult<Option<KitEncryptMode>> {
+    match obj {
+        None => Ok(None),
+        Some(obj) if obj.is_none() => Ok(None),
+        Some(obj) => {
+            if let Ok(mode) = obj.cast::<EncryptMode>() {
+                return Ok(Some((*mode.get()).into()));
+            }
+            match obj.extract::<u8>() {
+                Ok(0) => Ok(Some(KitEncryptMode::C1C3C2)),
+                Ok(1) => Ok(Some(KitEncryptMode::C1C2C3)),
+                _ => Err(PyValueError::new_err("Invalid mode")),
+            }
+        }
+    }
+}
+
+//
+//
+//
+/// `EncryptMode` of cncs-sm2-kit.
+#[pyclass(eq, eq_int, frozen, hash, module = "cncs_sm2")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EncryptMode {
+    C1C3C2 = 0,
+    C1C2C3 = 1,
+}
+
+impl From<EncryptMode> for KitEncryptMode {
+    fn from(mode: EncryptMode) -> Self {
+        match mode {
+            EncryptMode::C1C3C2 => KitEncryptMode::C1C3C2,
+            EncryptMode::C1C2C3 => KitEncryptMode::C1C2C3,
+        }
+    }
+}
+
+//
+//
+//
+#[pyclass(frozen, module = "cncs_sm2")]
+pub struct PrivateKey(KitPrivateKey);
+
+#[pymethods]
+impl PrivateKey {
+    #[staticmethod]
+    fn generate() -> Self {
+        Self(KitPrivateKey::random(&mut rand_core::OsRng))
+    }
+
+    #[staticmethod]
+    fn from_hex(hex: &str) -> PyResult<Self> {
+        KitPrivateKey::from_hex_str(hex)
+            .and_then(validated_private_key)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        KitPrivateKey::from_bytes(bytes)
+            .and_then(validated_private_key)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// PKCS#8, or SEC1 ECPrivateKey.
+    #[staticmethod]
+    fn from_der(der: &[u8]) -> PyResult<Self> {
+        KitPrivateKey::from_der(der)
+            .and_then(validated_private_key)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn from_pem(pem: &str) -> PyResult<Self> {
+        KitPrivateKey::from_pem(pem)
+            .and_then(validated_private_key)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    fn to_hex(&self) -> String {
+        self.0.to_hex_str()
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.0.as_array())
+    }
+
+    fn to_der<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.to_der())
+    }
+
+    fn to_pem(&self) -> String {
+        self.0.to_pem()
+    }
+
+    fn public_key(&self) -> PublicKey {
+        PublicKey(self.0.public_key())
+    }
+
+    #[pyo3(signature = (msg, user_id=None))]
+    fn sign(
+        &self,
+        py: Python<'_>,
+        msg: &Bound<'_, PyAny>,
+        user_id: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Signature> {
+        let msg = extract_bytes(msg, "msg")?;
+        let user_id = extract_user_id(user_id)?;
+        py.detach(|| kit_sign(&self.0, msg, user_id.as_deref()))
+            .map(Signature)
+            .map_err(to_py_err)
+    }
+
+    #[pyo3(signature = (ciphertext, mode=None))]
+    fn decrypt<'py>(
+        &self,
+        py: Python<'py>,
+        ciphertext: &[u8],
+        mode: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let mode = extract_mode(mode)?;
+        let msg = py
+            .detach(|| kit_decrypt(&self.0, ciphertext, mode))
+            .map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &msg))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __repr__(&self) -> String {
+        "PrivateKey(..)".into()
+    }
+}
+
+fn validated_private_key(private_key: KitPrivateKey) -> Result<KitPrivateKey, Error> {
+    private_key.validate().map(|_| private_key)
+}
+
+//
+//
+//
+#[pyclass(frozen, module = "cncs_sm2")]
+pub struct PublicKey(KitPublicKey);
+
+#[pymethods]
+impl PublicKey {
+    /// x || y, with or without the 04 prefix.
+    #[staticmethod]
+    fn from_hex(hex: &str) -> PyResult<Self> {
+        KitPublicKey::from_concated_hex_str(hex)
+            .and_then(validated_public_key)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        KitPublicKey::from_concated_bytes(bytes)
+            .and_then(validated_public_key)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// SubjectPublicKeyInfo.
+    #[staticmethod]
+    fn from_der(der: &[u8]) -> PyResult<Self> {
+        KitPublicKey::from_der(der)
+            .and_then(validated_public_key)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn from_pem(pem: &str) -> PyResult<Self> {
+        KitPublicKey::from_pem(pem)
+            .and_then(validated_public_key)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    fn to_hex(&self) -> String {
+        self.0.to_concated_hex_str()
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.0.as_concated_array())
+    }
+
+    fn to_der<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.to_der())
+    }
+
+    fn to_pem(&self) -> String {
+        self.0.to_pem()
+    }
+
+    #[pyo3(signature = (msg, signature, user_id=None))]
+    fn verify(
+        &self,
+        py: Python<'_>,
+        msg: &Bound<'_, PyAny>,
+        signature: &Signature,
+        user_id: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        let msg = extract_bytes(msg, "msg")?;
+        let user_id = extract_user_id(user_id)?;
+        py.detach(|| kit_verify(&self.0, msg, user_id.as_deref(), &signature.0))
+            .map_err(to_py_err)
+    }
+
+    #[pyo3(signature = (msg, mode=None))]
+    fn encrypt<'py>(
+        &self,
+        py: Python<'py>,
+        msg: &Bound<'_, PyAny>,
+        mode: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let msg = extract_bytes(msg, "msg")?;
+        let mode = extract_mode(mode)?;
+        let ciphertext = py
+            .detach(|| kit_encrypt(&self.0, msg, mode))
+            .map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &ciphertext))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __hash__(&self) -> u64 {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PublicKey('{}')", self.0.to_concated_hex_str())
+    }
+}
+
+fn validated_public_key(public_key: KitPublicKey) -> Result<KitPublicKey, Error> {
+    public_key.validate().map(|_| public_key)
+}
+
+//
+//
+//
+#[pyclass(frozen, module = "cncs_sm2")]
+pub struct Signature(KitSignature);
+
+#[pymethods]
+impl Signature {
+    /// r || s.
+    #[staticmethod]
+    fn from_hex(hex: &str) -> PyResult<Self> {
+        KitSignature::from_concated_hex_str(hex)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        KitSignature::from_concated_bytes(bytes)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn from_der(der: &[u8]) -> PyResult<Self> {
+        KitSignature::from_der(der).map(Self).map_err(to_py_err)
+    }
+
+    fn to_hex(&self) -> String {
+        self.0.to_concated_hex_str()
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.0.as_concated_array())
+    }
+
+    fn to_der<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.to_der())
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __hash__(&self) -> u64 {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Signature('{}')", self.0.to_concated_hex_str())
+    }
+}
+
+//
+// The functions of the PHP extension, on hex keys and base64 ciphertexts
+//
+#[pyfunction]
+#[pyo3(signature = (private_key_hex, msg, user_id=None))]
+fn sign(
+    py: Python<'_>,
+    private_key_hex: &str,
+    msg: &Bound<'_, PyAny>,
+    user_id: Option<&Bound<'_, PyAny>>,
+) -> PyResult<String> {
+    let private_key = PrivateKey::from_hex(private_key_hex)?;
+    Ok(private_key.sign(py, msg, user_id)?.to_hex())
+}
+
+#[pyfunction]
+#[pyo3(signature = (public_key_hex, msg, user_id, signature_hex))]
+fn verify(
+    py: Python<'_>,
+    public_key_hex: &str,
+    msg: &Bound<'_, PyAny>,
+    user_id: Option<&Bound<'_, PyAny>>,
+    signature_hex: &str,
+) -> PyResult<bool> {
+    let public_key = PublicKey::from_hex(public_key_hex)?;
+    let signature = Signature::from_hex(signature_hex)?;
+    public_key.verify(py, msg, &signature, user_id)
+}
+
+#[pyfunction]
+#[pyo3(signature = (public_key_hex, msg, mode=None))]
+fn encrypt(
+    py: Python<'_>,
+    public_key_hex: &str,
+    msg: &Bound<'_, PyAny>,
+    mode: Option<&Bound<'_, PyAny>>,
+) -> PyResult<String> {
+    let public_key = PublicKey::from_hex(public_key_hex)?;
+    let ciphertext = public_key.encrypt(py, msg, mode)?;
+    Ok(base64::encode(ciphertext.as_bytes()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (private_key_hex, msg_encrypted_base64, mode=None))]
+fn decrypt<'py>(
+    py: Python<'py>,
+    private_key_hex: &str,
+    msg_encrypted_base64: &str,
+    mode: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let private_key = PrivateKey::from_hex(private_key_hex)?;
+    let ciphertext = base64::decode(msg_encrypted_base64)
+        .map_err(|err| ParseError::new_err(format!("invalid base64: {}", err)))?;
+    private_key.decrypt(py, &ciphertext, mode)
+}
+
+#[pymodule]
+fn cncs_sm2(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("Sm2Error", py.get_type::<Sm2Error>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add("InvalidKeyError", py.get_type::<InvalidKeyError>())?;
+    m.add(
+        "InvalidSignatureError",
+        py.get_type::<InvalidSignatureError>(),
+    )?;
+    m.add("InvalidUserIdError", py.get_type::<InvalidUserIdError>())?;
+    m.add("DecryptionError", py.get_type::<DecryptionError>())?;
+
+    m.add_class::<EncryptMode>()?;
+    m.add_class::<PrivateKey>()?;
+    m.add_class::<PublicKey>()?;
+    m.add_class::<Signature>()?;
+
+    m.add_function(wrap_pyfunction!(sign, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(encrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(decrypt, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,164 @@
+import base64
+from pathlib import Path
+
+import pytest
+
+import cncs_sm2
+from cncs_sm2 import EncryptMode, PrivateKey, PublicKey, Signature
+
+
+def read_vectors():
+    path = Path(__file__).resolve().parents[2] / "fixtures" / "vectors.txt"
+    vectors = {}
+    for line in path.read_text().splitlines():
+        if line and not line.startswith("#"):
+            name, value = line.split(" ", 1)
+            vectors[name] = value.replace("\\n", "\n")
+    return vectors
+
+
+# The vectors of test.php in cncs-sm2-php and of OpenSSL
+VECTORS = read_vectors()
+PRIVATE_KEY = VECTORS["PRIVATE_KEY"]
+PUBLIC_KEY = VECTORS["PUBLIC_KEY"]
+SIGNATURE = VECTORS["SIGNATURE"]
+SIGNATURE_WITH_USER_ID = VECTORS["SIGNATURE_WITH_USER_ID"]
+CIPHERTEXT_C1C3C2 = base64.b64encode(bytes.fromhex(VECTORS["CIPHERTEXT_C1C3C2"])).decode()
+CIPHERTEXT_C1C2C3 = base64.b64encode(bytes.fromhex(VECTORS["CIPHERTEXT_C1C2C3"])).decode()
+
+OPENSSL_PRIVATE_KEY = VECTORS["OPENSSL_PRIVATE_KEY"]
+OPENSSL_PRIVATE_KEY_PEM = VECTORS["OPENSSL_PRIVATE_KEY_PEM"]
+OPENSSL_PUBLIC_KEY_PEM = VECTORS["OPENSSL_PUBLIC_KEY_PEM"]
+# With an empty user id
+OPENSSL_SIGNATURE_DER = bytes.fromhex(VECTORS["OPENSSL_SIGNATURE_DER"])
+
+
+def test_sign():
+    for user_id in [None, "1234567812345678", b"1234567812345678"]:
+        signature_hex = cncs_sm2.sign(PRIVATE_KEY, "TEST", user_id)
+        assert len(signature_hex) == 128
+        assert cncs_sm2.verify(PUBLIC_KEY, "TEST", user_id, signature_hex)
+
+
+def test_verify():
+    assert cncs_sm2.verify(PUBLIC_KEY, "TEST", None, SIGNATURE)
+    assert cncs_sm2.verify(PUBLIC_KEY, b"TEST", None, SIGNATURE)
+    assert cncs_sm2.verify(PUBLIC_KEY, "TEST", "1234567812345678", SIGNATURE_WITH_USER_ID)
+    assert cncs_sm2.verify(PUBLIC_KEY, "TEST", b"1234567812345678", SIGNATURE_WITH_USER_ID)
+
+    assert not cncs_sm2.verify(PUBLIC_KEY, "TEST2", None, SIGNATURE)
+    assert not cncs_sm2.verify(PUBLIC_KEY, "TEST", "ALICE123@YAHOO.COM", SIGNATURE)
+
+
+def test_encrypt():
+    for mode in [None, 0, 1, EncryptMode.C1C3C2, EncryptMode.C1C2C3]:
+        msg_encrypted_base64 = cncs_sm2.encrypt(PUBLIC_KEY, "TEST", mode)
+        assert cncs_sm2.decrypt(PRIVATE_KEY, msg_encrypted_base64, mode) == b"TEST"
+
+
+def test_decrypt():
+    assert cncs_sm2.decrypt(PRIVATE_KEY, CIPHERTEXT_C1C3C2, None) == b"TEST"
+    assert cncs_sm2.decrypt(PRIVATE_KEY, CIPHERTEXT_C1C3C2, EncryptMode.C1C3C2) == b"TEST"
+    assert cncs_sm2.decrypt(PRIVATE_KEY, CIPHERTEXT_C1C2C3, 1) == b"TEST"
+    assert cncs_sm2.decrypt(PRIVATE_KEY, CIPHERTEXT_C1C2C3, EncryptMode.C1C2C3) == b"TEST"
+
+    with pytest.raises(cncs_sm2.DecryptionError):
+        cncs_sm2.decrypt(PRIVATE_KEY, CIPHERTEXT_C1C2C3, None)
+
+
+def test_keys():
+    private_key = PrivateKey.from_hex(PRIVATE_KEY)
+    public_key = private_key.public_key()
+    assert public_key == PublicKey.from_hex(PUBLIC_KEY)
+    assert public_key == PublicKey.from_hex("04" + PUBLIC_KEY.lower())
+    assert public_key.to_hex() == PUBLIC_KEY
+    assert private_key.to_hex() == PRIVATE_KEY
+
+    assert PrivateKey.from_bytes(private_key.to_bytes()) == private_key
+    assert PrivateKey.from_der(private_key.to_der()) == private_key
+    assert PrivateKey.from_pem(private_key.to_pem()) == private_key
+    assert PublicKey.from_bytes(public_key.to_bytes()) == public_key
+    assert PublicKey.from_bytes(b"\x04" + public_key.to_bytes()) == public_key
+    assert PublicKey.from_der(public_key.to_der()) == public_key
+    assert PublicKey.from_pem(public_key.to_pem()) == public_key
+    assert len({public_key, PublicKey.from_hex(PUBLIC_KEY)}) == 1
+
+    private_key = PrivateKey.generate()
+    assert private_key != PrivateKey.generate()
+    assert PrivateKey.from_hex(private_key.to_hex()) == private_key
+    assert "PrivateKey" in repr(private_key)
+    assert private_key.to_hex() not in repr(private_key)
+
+
+def test_openssl_keys():
+    private_key = PrivateKey.from_pem(OPENSSL_PRIVATE_KEY_PEM)
+    assert private_key.to_hex() == OPENSSL_PRIVATE_KEY
+    assert private_key.to_pem() == OPENSSL_PRIVATE_KEY_PEM
+
+    public_key = PublicKey.from_pem(OPENSSL_PUBLIC_KEY_PEM)
+    assert public_key == private_key.public_key()
+    assert public_key.to_pem() == OPENSSL_PUBLIC_KEY_PEM
+
+    signature = Signature.from_der(OPENSSL_SIGNATURE_DER)
+    assert signature.to_der() == OPENSSL_SIGNATURE_DER
+    assert public_key.verify(b"TEST", signature, b"")
+    assert not public_key.verify(b"TEST", signature)
+
+
+def test_sign_and_verify():
+    private_key = PrivateKey.generate()
+    public_key = private_key.public_key()
+    for user_id in [None, "ALICE123@YAHOO.COM", "用户".encode(), b"\xff\x00"]:
+        signature = private_key.sign(b"\x00\xff", user_id)
+        assert public_key.verify(b"\x00\xff", signature, user_id)
+        assert Signature.from_hex(signature.to_hex()) == signature
+        assert Signature.from_bytes(signature.to_bytes()) == signature
+        assert Signature.from_der(signature.to_der()) == signature
+        assert not public_key.verify(b"\x00\xfe", signature, user_id)
+
+
+def test_encrypt_and_decrypt():
+    private_key = PrivateKey.generate()
+    public_key = private_key.public_key()
+    msg = bytes(range(256))
+    for mode in [None, EncryptMode.C1C3C2, EncryptMode.C1C2C3]:
+        ciphertext = public_key.encrypt(msg, mode)
+        assert len(ciphertext) == 1 + 64 + 32 + len(msg)
+        assert private_key.decrypt(ciphertext, mode) == msg
+
+        tampered = ciphertext[:-1] + bytes([ciphertext[-1] ^ 1])
+        with pytest.raises(cncs_sm2.DecryptionError):
+            private_key.decrypt(tampered, mode)
+
+
+def test_errors():
+    for error in [
+        cncs_sm2.ParseError,
+        cncs_sm2.InvalidKeyError,
+        cncs_sm2.InvalidSignatureError,
+        cncs_sm2.InvalidUserIdError,
+        cncs_sm2.DecryptionError,
+    ]:
+        assert issubclass(error, cncs_sm2.Sm2Error)
+    assert issubclass(cncs_sm2.Sm2Error, ValueError)
+
+    with pytest.raises(cncs_sm2.ParseError):
+        PrivateKey.from_hex("XYZ")
+    with pytest.raises(cncs_sm2.ParseError):
+        PublicKey.from_hex("04")
+    with pytest.raises(cncs_sm2.ParseError):
+        cncs_sm2.decrypt(PRIVATE_KEY, "!!!", None)
+    with pytest.raises(cncs_sm2.InvalidKeyError):
+        PrivateKey.from_hex("00")
+    with pytest.raises(cncs_sm2.InvalidKeyError):
+        PublicKey.from_hex(PUBLIC_KEY[:-1] + "B")
+    with pytest.raises(cncs_sm2.ParseError):
+        PublicKey.from_pem(OPENSSL_PRIVATE_KEY_PEM)
+    with pytest.raises(cncs_sm2.InvalidSignatureError):
+        Signature.from_hex("00" * 64)
+    with pytest.raises(cncs_sm2.InvalidUserIdError):
+        cncs_sm2.sign(PRIVATE_KEY, "TEST", "A" * 8192)
+    with pytest.raises(ValueError):
+        cncs_sm2.encrypt(PUBLIC_KEY, "TEST", 2)
+    with pytest.raises(TypeError):
+        cncs_sm2.sign(PRIVATE_KEY, 1)
@@ -0,0 +1,3 @@
+# frozen_string_literal: true
+
+require_relative "cncs_sm2/cncs_sm2"
@@ -0,0 +1,307 @@
+use cncs_sm2_kit::{
+    decrypt as kit_decrypt, encrypt as kit_encrypt, sign as kit_sign,
+    types::{
+        EncryptMode, PrivateKey as KitPrivateKey, PublicKey as KitPublicKey,
+        Signature as KitSignature,
+    },
+    verify as kit_verify, Error,
+};
+use magnus::{
+    function, method, prelude::*, scan_args::scan_args, value::Lazy, Error as RbError,
+    ExceptionClass, RModule, RString, Ruby, TryConvert, Value,
+};
+
+static MODULE: Lazy<RModule> = Lazy::new(|ruby| ruby.define_module("CncsSm2").unwrap());
+
+static SM2_ERROR: Lazy<ExceptionClass> = Lazy::new(|ruby| {
+    ruby.get_inner(&MODULE)
+        .define_error("Error", ruby.exception_standard_error())
+        .unwrap()
+});
+
+fn define_sm2_error(ruby: &Ruby, name: &str) -> ExceptionClass {
+    ruby.get_inner(&MODULE)
+        .define_error(name, ruby.get_inner(&SM2_ERROR))
+        .unwrap()
+}
+
+static PARSE_ERROR: Lazy<ExceptionClass> = Lazy::new(|ruby| define_sm2_error(ruby, "ParseError"));
+static INVALID_KEY_ERROR: Lazy<ExceptionClass> =
+    Lazy::new(|ruby| define_sm2_error(ruby, "InvalidKeyError"));
+static INVALID_SIGNATURE_ERROR: Lazy<ExceptionClass> =
+    Lazy::new(|ruby| define_sm2_error(ruby, "InvalidSignatureError"));
+static INVALID_USER_ID_ERROR: Lazy<ExceptionClass> =
+    Lazy::new(|ruby| define_sm2_error(ruby, "InvalidUserIdError"));
+static DECRYPTION_ERROR: Lazy<ExceptionClass> =
+    Lazy::new(|ruby| define_sm2_error(ruby, "DecryptionError"));
+
+fn to_rb_err(err: Error) -> RbError {
+    let ruby = Ruby::get().expect("called from Ruby");
+    let class = match err {
+        Error::ParseHex(_) | Error::InvalidLength(_) | Error::InvalidPrefix | Error::Malformed => {
+            &PARSE_ERROR
+        }
+        Error::InvalidPrivateKey | Error::InvalidPublicKey => &INVALID_KEY_ERROR,
+        Error::InvalidSignature => &INVALID_SIGNATURE_ERROR,
+        Error::InvalidUserId => &INVALID_USER_ID_ERROR,
+        Error::InvalidCiphertext => &DECRYPTION_ERROR,
+        _ => &SM2_ERROR,
+    };
+    RbError::new(ruby.get_inner(class), err.to_string())
+}
+
+//
+//
+//
+/// The bytes of any String, whatever its encoding.
+fn bytes(string: RString) -> Vec<u8> {
+    // Copied before anything can call back into Ruby and move the string
+    unsafe { string.as_slice() }.to_vec()
+}
+
+/// `CncsSm2::C1C3C2` or `CncsSm2::C1C2C3`, the `mode` of the PHP extension.
+fn mode(ruby: &Ruby, mode: Option<i64>) -> Result<EncryptMode, RbError> {
+    match mode {
+        None | Some(0) => Ok(EncryptMode::C1C3C2),
+        Some(1) => Ok(EncryptMode::C1C2C3),
+        Some(_) => Err(RbError::new(ruby.exception_arg_error(), "invalid mode")),
+    }
+}
+
+fn validated_private_key(private_key: KitPrivateKey) -> Result<PrivateKey, RbError> {
+    private_key.validate().map_err(to_rb_err)?;
+    Ok(PrivateKey(private_key))
+}
+
+fn validated_public_key(public_key: KitPublicKey) -> Result<PublicKey, RbError> {
+    public_key.validate().map_err(to_rb_err)?;
+    Ok(PublicKey(public_key))
+}
+
+//
+//
+//
+#[magnus::wrap(class = "CncsSm2::PrivateKey", free_immediately, size)]
+struct PrivateKey(KitPrivateKey);
+
+impl PrivateKey {
+    fn generate() -> Self {
+        Self(KitPrivateKey::random(&mut rand_core::OsRng))
+    }
+
+    fn from_hex(hex: String) -> Result<Self, RbError> {
+        KitPrivateKey::from_hex_str(&hex)
+            .map_err(to_rb_err)
+            .and_then(validated_private_key)
+    }
+
+    fn from_bytes(bytes: RString) -> Result<Self, RbError> {
+        KitPrivateKey::from_bytes(&self::bytes(bytes))
+            .map_err(to_rb_err)
+            .and_then(validated_private_key)
+    }
+
+    /// PKCS#8, or SEC1 ECPrivateKey.
+    fn from_der(der: RString) -> Result<Self, RbError> {
+        KitPrivateKey::from_der(&bytes(der))
+            .map_err(to_rb_err)
+            .and_then(validated_private_key)
+    }
+
+    fn from_pem(pem: String) -> Result<Self, RbError> {
+        KitPrivateKey::from_pem(&pem)
+            .map_err(to_rb_err)
+            .and_then(validated_private_key)
+    }
+
+    fn to_hex(&self) -> String {
+        self.0.to_hex_str()
+    }
+
+    fn to_bytes(ruby: &Ruby, rb_self: &Self) -> RString {
+        ruby.str_from_slice(rb_self.0.as_array())
+    }
+
+    fn to_der(ruby: &Ruby, rb_self: &Self) -> RString {
+        ruby.str_from_slice(&rb_self.0.to_der())
+    }
+
+    fn to_pem(&self) -> String {
+        self.0.to_pem()
+    }
+
+    fn public_key(&self) -> PublicKey {
+        PublicKey(self.0.public_key())
+    }
+
+    fn eq(&self, other: Value) -> bool {
+        <&Self>::try_convert(other).is_ok_and(|other| other.0 == self.0)
+    }
+
+    // Keeps d out of logs
+    fn inspect(&self) -> String {
+        "#<CncsSm2::PrivateKey>".to_owned()
+    }
+}
+
+#[magnus::wrap(class = "CncsSm2::PublicKey", free_immediately, size)]
+struct PublicKey(KitPublicKey);
+
+impl PublicKey {
+    /// x || y as 128 hex characters, with or without the 04 prefix.
+    fn from_hex(hex: String) -> Result<Self, RbError> {
+        KitPublicKey::from_concated_hex_str(&hex)
+            .map_err(to_rb_err)
+            .and_then(validated_public_key)
+    }
+
+    fn from_bytes(bytes: RString) -> Result<Self, RbError> {
+        KitPublicKey::from_concated_bytes(&self::bytes(bytes))
+            .map_err(to_rb_err)
+            .and_then(validated_public_key)
+    }
+
+    /// SubjectPublicKeyInfo.
+    fn from_der(der: RString) -> Result<Self, RbError> {
+        KitPublicKey::from_der(&bytes(der))
+            .map_err(to_rb_err)
+            .and_then(validated_public_key)
+    }
+
+    fn from_pem(pem: String) -> Result<Self, RbError> {
+        KitPublicKey::from_pem(&pem)
+            .map_err(to_rb_err)
+            .and_then(validated_public_key)
+    }
+
+    fn to_hex(&self) -> String {
+        self.0.to_concated_hex_str()
+    }
+
+    fn to_bytes(ruby: &Ruby, rb_self: &Self) -> RString {
+        ruby.str_from_slice(rb_self.0.as_concated_array())
+    }
+
+    fn to_der(ruby: &Ruby, rb_self: &Self) -> RString {
+        ruby.str_from_slice(&rb_self.0.to_der())
+    }
+
+    fn to_pem(&self) -> String {
+        self.0.to_pem()
+    }
+
+    fn eq(&self, other: Value) -> bool {
+        <&Self>::try_convert(other).is_ok_and(|other| other.0 == self.0)
+    }
+
+    fn inspect(&self) -> String {
+        format!("#<CncsSm2::PublicKey {}>", self.0.to_concated_hex_str())
+    }
+}
+
+//
+//
+//
+/// `sign(private_key, msg, user_id = nil)`, the 64 bytes of r || s.
+fn sign(ruby: &Ruby, args: &[Value]) -> Result<RString, RbError> {
+    let args =
+        scan_args::<(&PrivateKey, RString), (Option<Option<RString>>,), (), (), (), ()>(args)?;
+    let (private_key, msg) = args.required;
+    let (user_id,) = args.optional;
+    let user_id = user_id.flatten().map(bytes);
+
+    let signature = kit_sign(&private_key.0, &bytes(msg), user_id.as_deref()).map_err(to_rb_err)?;
+    Ok(ruby.str_from_slice(signature.as_concated_array()))
+}
+
+/// `verify(public_key, msg, signature, user_id = nil)`, with the 64 bytes of r || s.
+fn verify(args: &[Value]) -> Result<bool, RbError> {
+    let args =
+        scan_args::<(&PublicKey, RString, RString), (Option<Option<RString>>,), (), (), (), ()>(
+            args,
+        )?;
+    let (public_key, msg, signature) = args.required;
+    let (user_id,) = args.optional;
+    let user_id = user_id.flatten().map(bytes);
+
+    let signature = KitSignature::from_concated_bytes(&bytes(signature)).map_err(to_rb_err)?;
+    kit_verify(&public_key.0, &bytes(msg), user_id.as_deref(), &signature).map_err(to_rb_err)
+}
+
+/// `encrypt(public_key, msg, mode = CncsSm2::C1C3C2)`, the 04-prefixed ciphertext.
+fn encrypt(ruby: &Ruby, args: &[Value]) -> Result<RString, RbError> {
+    let args = scan_args::<(&PublicKey, RString), (Option<Option<i64>>,), (), (), (), ()>(args)?;
+    let (public_key, msg) = args.required;
+    let (mode,) = args.optional;
+    let mode = self::mode(ruby, mode.flatten())?;
+
+    let ciphertext = kit_encrypt(&public_key.0, &bytes(msg), mode).map_err(to_rb_err)?;
+    Ok(ruby.str_from_slice(&ciphertext))
+}
+
+/// `decrypt(private_key, ciphertext, mode = CncsSm2::C1C3C2)`.
+fn decrypt(ruby: &Ruby, args: &[Value]) -> Result<RString, RbError> {
+    let args = scan_args::<(&PrivateKey, RString), (Option<Option<i64>>,), (), (), (), ()>(args)?;
+    let (private_key, ciphertext) = args.required;
+    let (mode,) = args.optional;
+    let mode = self::mode(ruby, mode.flatten())?;
+
+    let msg = kit_decrypt(&private_key.0, &bytes(ciphertext), mode).map_err(to_rb_err)?;
+    Ok(ruby.str_from_slice(&msg))
+}
+
+//
+//
+//
+#[magnus::init(name = "cncs_sm2")]
+fn init(ruby: &Ruby) -> Result<(), RbError> {
+    let module = ruby.get_inner(&MODULE);
+    for error in [
+        &SM2_ERROR,
+        &PARSE_ERROR,
+        &INVALID_KEY_ERROR,
+        &INVALID_SIGNATURE_ERROR,
+        &INVALID_USER_ID_ERROR,
+        &DECRYPTION_ERROR,
+    ] {
+        Lazy::force(error, ruby);
+    }
+
+    module.const_set("C1C3C2", 0)?;
+    module.const_set("C1C2C3", 1)?;
+
+    let class = module.define_class("PrivateKey", ruby.class_object())?;
+    class.undef_default_alloc_func();
+    class.define_singleton_method("generate", function!(PrivateKey::generate, 0))?;
+    class.define_singleton_method("from_hex", function!(PrivateKey::from_hex, 1))?;
+    class.define_singleton_method("from_bytes", function!(PrivateKey::from_bytes, 1))?;
+    class.define_singleton_method("from_der", function!(PrivateKey::from_der, 1))?;
+    class.define_singleton_method("from_pem", function!(PrivateKey::from_pem, 1))?;
+    class.define_method("to_hex", method!(PrivateKey::to_hex, 0))?;
+    class.define_method("to_bytes", method!(PrivateKey::to_bytes, 0))?;
+    class.define_method("to_der", method!(PrivateKey::to_der, 0))?;
+    class.define_method("to_pem", method!(PrivateKey::to_pem, 0))?;
+    class.define_method("public_key", method!(PrivateKey::public_key, 0))?;
+    class.define_method("==", method!(PrivateKey::eq, 1))?;
+    class.define_method("inspect", method!(PrivateKey::inspect, 0))?;
+
+    let class = module.define_class("PublicKey", ruby.class_object())?;
+    class.undef_default_alloc_func();
+    class.define_singleton_method("from_hex", function!(PublicKey::from_hex, 1))?;
+    class.define_singleton_method("from_bytes", function!(PublicKey::from_bytes, 1))?;
+    class.define_singleton_method("from_der", function!(PublicKey::from_der, 1))?;
+    class.define_singleton_method("from_pem", function!(PublicKey::from_pem, 1))?;
+    class.define_method("to_hex", method!(PublicKey::to_hex, 0))?;
+    class.define_method("to_bytes", method!(PublicKey::to_bytes, 0))?;
+    class.define_method("to_der", method!(PublicKey::to_der, 0))?;
+    class.define_method("to_pem", method!(PublicKey::to_pem, 0))?;
+    class.define_method("==", method!(PublicKey::eq, 1))?;
+    class.define_method("inspect", method!(PublicKey::inspect, 0))?;
+
+    module.define_module_function("sign", function!(sign, -1))?;
+    module.define_module_function("verify", function!(verify, -1))?;
+    module.define_module_function("encrypt", function!(encrypt, -1))?;
+    module.define_module_function("decrypt", function!(decrypt, -1))?;
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+# frozen_string_literal: true
+
+require "minitest/autorun"
+require "cncs_sm2"
+
+# fixtures/vectors.txt, the vectors of cncs-sm2-php/test.php and of OpenSSL
+VECTORS = File.readlines(File.expand_path("../../fixtures/vectors.txt", __dir__), chomp: true)
+              .reject { |line| line.empty? || line.start_with?("#") }
+              .to_h { |line| line.split(" ", 2) }
+              .transform_values { |value| value.gsub('\n', "\n") }
+              .freeze
+
+class TestCncsSm2 < Minitest::Test
+  PRIVATE_KEY = VECTORS.fetch("PRIVATE_KEY")
+  PUBLIC_KEY = VECTORS.fetch("PUBLIC_KEY")
+  SIGNATURE = VECTORS.fetch("SIGNATURE")
+  SIGNATURE_WITH_USER_ID = VECTORS.fetch("SIGNATURE_WITH_USER_ID")
+  CIPHERTEXT_C1C3C2 = VECTORS.fetch("CIPHERTEXT_C1C3C2")
+  CIPHERTEXT_C1C2C3 = VECTORS.fetch("CIPHERTEXT_C1C2C3")
+
+  def setup
+    @private_key = CncsSm2::PrivateKey.from_hex(PRIVATE_KEY)
+    @public_key = CncsSm2::PublicKey.from_hex(PUBLIC_KEY)
+  end
+
+  def hex(str)
+    [str].pack("H*")
+  end
+
+  def test_keys
+    assert_equal @public_key, @private_key.public_key
+    assert_equal PRIVATE_KEY, @private_key.to_hex
+    assert_equal PUBLIC_KEY, @public_key.to_hex
+    assert_equal @public_key, CncsSm2::PublicKey.from_hex("04#{PUBLIC_KEY}")
+
+    assert_equal @private_key, CncsSm2::PrivateKey.from_bytes(@private_key.to_bytes)
+    assert_equal @private_key, CncsSm2::PrivateKey.from_der(@private_key.to_der)
+    assert_equal @private_key, CncsSm2::PrivateKey.from_pem(@private_key.to_pem)
+    assert_equal @public_key, CncsSm2::PublicKey.from_bytes(@public_key.to_bytes)
+    assert_equal @public_key, CncsSm2::PublicKey.from_der(@public_key.to_der)
+    assert_equal @public_key, CncsSm2::PublicKey.from_pem(@public_key.to_pem)
+
+    assert_equal Encoding::BINARY, @private_key.to_bytes.encoding
+    assert_equal 32, @private_key.to_bytes.bytesize
+    assert_equal 64, @public_key.to_bytes.bytesize
+
+    refute_equal CncsSm2::PrivateKey.generate, CncsSm2::PrivateKey.generate
+    refute_includes @private_key.inspect, PRIVATE_KEY
+  end
+
+  def test_sign
+    signature = CncsSm2.sign(@private_key, "TEST")
+    assert_equal 64, signature.bytesize
+    assert CncsSm2.verify(@public_key, "TEST", signature)
+
+    signature = CncsSm2.sign(@private_key, "TEST", "1234567812345678")
+    assert CncsSm2.verify(@public_key, "TEST", signature, "1234567812345678")
+
+    signature = CncsSm2.sign(@private_key, "TEST", "ALICE123@YAHOO.COM")
+    refute CncsSm2.verify(@public_key, "TEST", signature)
+  end
+
+  def test_verify
+    assert CncsSm2.verify(@public_key, "TEST", hex(SIGNATURE))
+    assert CncsSm2.verify(@public_key, "TEST", hex(SIGNATURE), nil)
+    assert CncsSm2.verify(@public_key, "TEST", hex(SIGNATURE_WITH_USER_ID), "1234567812345678")
+    refute CncsSm2.verify(@public_key, "TEST2", hex(SIGNATURE_WITH_USER_ID), "1234567812345678")
+  end
+
+  def test_openssl
+    private_key = CncsSm2::PrivateKey.from_pem(VECTORS.fetch("OPENSSL_PRIVATE_KEY_PEM"))
+    public_key = CncsSm2::PublicKey.from_pem(VECTORS.fetch("OPENSSL_PUBLIC_KEY_PEM"))
+    assert_equal VECTORS.fetch("OPENSSL_PRIVATE_KEY"), private_key.to_hex
+    assert_equal public_key, private_key.public_key
+    assert_equal VECTORS.fetch("OPENSSL_PRIVATE_KEY_PEM"), private_key.to_pem
+    assert_equal VECTORS.fetch("OPENSSL_PUBLIC_KEY_PEM"), public_key.to_pem
+  end
+
+  def test_encrypt
+    [nil, CncsSm2::C1C3C2, CncsSm2::C1C2C3].each do |mode|
+      ciphertext = CncsSm2.encrypt(@public_key, "TEST", mode)
+      assert_equal 4 + 97, ciphertext.bytesize
+      assert_equal "TEST", CncsSm2.decrypt(@private_key, ciphertext, mode)
+    end
+
+    binary = (0..255).map(&:chr).join.b
+    assert_equal binary, CncsSm2.decrypt(@private_key, CncsSm2.encrypt(@public_key, binary))
+  end
+
+  def test_decrypt
+    assert_equal "TEST", CncsSm2.decrypt(@private_key, hex(CIPHERTEXT_C1C3C2))
+    assert_equal "TEST", CncsSm2.decrypt(@private_key, hex(CIPHERTEXT_C1C3C2), nil)
+    assert_equal "TEST", CncsSm2.decrypt(@private_key, hex(CIPHERTEXT_C1C2C3), CncsSm2::C1C2C3)
+  end
+
+  def test_errors
+    assert_raises(CncsSm2::ParseError) { CncsSm2::PrivateKey.from_hex("XYZ") }
+    assert_raises(CncsSm2::InvalidKeyError) { CncsSm2::PrivateKey.from_bytes("\0" * 32) }
+    assert_raises(CncsSm2::ParseError) { CncsSm2::PublicKey.from_pem(@private_key.to_pem) }
+    assert_raises(CncsSm2::ParseError) { CncsSm2.verify(@public_key, "TEST", "\0" * 63) }
+    assert_raises(CncsSm2::InvalidUserIdError) { CncsSm2.sign(@private_key, "TEST", "\0" * 8192) }
+    assert_raises(ArgumentError) { CncsSm2.encrypt(@public_key, "TEST", 2) }
+    assert_raises(TypeError) { CncsSm2.sign(@public_key, "TEST") }
+
+    ciphertext = hex(CIPHERTEXT_C1C3C2)
+    ciphertext[-1] = (ciphertext[-1].ord ^ 1).chr
+    error = assert_raises(CncsSm2::DecryptionError) { CncsSm2.decrypt(@private_key, ciphertext) }
+    assert_kind_of CncsSm2::Error, error
+    assert_kind_of StandardError, error
+  end
+end
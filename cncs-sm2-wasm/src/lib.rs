@@ -0,0 +1,179 @@
+use cncs_sm2_kit::{
+    encrypt_with_rng,
+    types::{EncryptMode as KitEncryptMode, PublicKey as KitPublicKey, Signature as KitSignature},
+    verify as kit_verify, Error,
+};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+fn to_js_err(err: Error) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+/// A string as UTF-8, or any bytes.
+fn user_id_bytes(user_id: &JsValue) -> Result<Option<Vec<u8>>, JsError> {
+    if user_id.is_undefined() || user_id.is_null() {
+        Ok(None)
+    } else if let Some(user_id) = user_id.as_string() {
+        Ok(Some(user_id.into_bytes()))
+    } else if let Some(user_id) = user_id.dyn_ref::<Uint8Array>() {
+        Ok(Some(user_id.to_vec()))
+    } else {
+        Err(JsError::new("userId must be a string or Uint8Array"))
+    }
+}
+
+//
+//
+//
+/// `EncryptMode` of cncs-sm2-kit.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EncryptMode {
+    C1C3C2 = 0,
+    C1C2C3 = 1,
+}
+
+impl From<EncryptMode> for KitEncryptMode {
+    fn from(mode: EncryptMode) -> Self {
+        match mode {
+            EncryptMode::C1C3C2 => KitEncryptMode::C1C3C2,
+            EncryptMode::C1C2C3 => KitEncryptMode::C1C2C3,
+        }
+    }
+}
+
+//
+//
+//
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct PublicKey(KitPublicKey);
+
+#[wasm_bindgen]
+impl PublicKey {
+    /// x || y, with or without the 04 prefix.
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<PublicKey, JsError> {
+        Self::validated(KitPublicKey::from_concated_hex_str(hex))
+    }
+
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey, JsError> {
+        Self::validated(KitPublicKey::from_concated_bytes(bytes))
+    }
+
+    /// SubjectPublicKeyInfo.
+    #[wasm_bindgen(js_name = fromDer)]
+    pub fn from_der(der: &[u8]) -> Result<PublicKey, JsError> {
+        Self::validated(KitPublicKey::from_der(der))
+    }
+
+    #[wasm_bindgen(js_name = fromPem)]
+    pub fn from_pem(pem: &str) -> Result<PublicKey, JsError> {
+        Self::validated(KitPublicKey::from_pem(pem))
+    }
+
+    fn validated(public_key: Result<KitPublicKey, Error>) -> Result<PublicKey, JsError> {
+        let public_key = public_key.map_err(to_js_err)?;
+        public_key.validate().map_err(to_js_err)?;
+        Ok(Self(public_key))
+    }
+
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        self.0.to_concated_hex_str()
+    }
+
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_concated_bytes()
+    }
+
+    #[wasm_bindgen(js_name = toDer)]
+    pub fn to_der(&self) -> Vec<u8> {
+        self.0.to_der()
+    }
+
+    #[wasm_bindgen(js_name = toPem)]
+    pub fn to_pem(&self) -> String {
+        self.0.to_pem()
+    }
+
+    /// The 04-prefixed ciphertext, C1C3C2 unless `mode` says otherwise.
+    pub fn encrypt(&self, msg: &[u8], mode: Option<EncryptMode>) -> Result<Vec<u8>, JsError> {
+        let mode = mode.map(KitEncryptMode::from).unwrap_or_default();
+        encrypt_with_rng(&mut rand_core::OsRng, &self.0, msg, mode).map_err(to_js_err)
+    }
+
+    /// The UTF-8 bytes of `msg`, encrypted and base64 encoded as `sm2_decrypt` of the PHP
+    /// extension expects.
+    #[wasm_bindgen(js_name = encryptToBase64)]
+    pub fn encrypt_to_base64(
+        &self,
+        msg: &str,
+        mode: Option<EncryptMode>,
+    ) -> Result<String, JsError> {
+        let mode = mode.map(KitEncryptMode::from).unwrap_or_default();
+        let bytes =
+            encrypt_with_rng(&mut rand_core::OsRng, &self.0, msg, mode).map_err(to_js_err)?;
+        Ok(base64_simd::STANDARD.encode_to_string(bytes))
+    }
+
+    /// `userId` defaults to 1234567812345678.
+    pub fn verify(
+        &self,
+        msg: &[u8],
+        signature: &Signature,
+        #[wasm_bindgen(js_name = userId, unchecked_param_type = "string | Uint8Array | undefined")]
+        user_id: JsValue,
+    ) -> Result<bool, JsError> {
+        let user_id = user_id_bytes(&user_id)?;
+        kit_verify(&self.0, msg, user_id.as_deref(), &signature.0).map_err(to_js_err)
+    }
+}
+
+//
+//
+//
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Signature(KitSignature);
+
+#[wasm_bindgen]
+impl Signature {
+    /// r || s.
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<Signature, JsError> {
+        KitSignature::from_concated_hex_str(hex)
+            .map(Self)
+            .map_err(to_js_err)
+    }
+
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Signature, JsError> {
+        KitSignature::from_concated_bytes(bytes)
+            .map(Self)
+            .map_err(to_js_err)
+    }
+
+    #[wasm_bindgen(js_name = fromDer)]
+    pub fn from_der(der: &[u8]) -> Result<Signature, JsError> {
+        KitSignature::from_der(der).map(Self).map_err(to_js_err)
+    }
+
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        self.0.to_concated_hex_str()
+    }
+
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_concated_bytes()
+    }
+
+    #[wasm_bindgen(js_name = toDer)]
+    pub fn to_der(&self) -> Vec<u8> {
+        self.0.to_der()
+    }
+}
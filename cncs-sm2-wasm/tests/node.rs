@@ -0,0 +1,160 @@
+#![cfg(target_arch = "wasm32")]
+
+use cncs_sm2_kit::{
+    decrypt, sign_with_rng,
+    types::{EncryptMode as KitEncryptMode, PrivateKey},
+};
+use cncs_sm2_wasm::{EncryptMode, PublicKey, Signature};
+use js_sys::Uint8Array;
+use rand_core::OsRng;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+// The vectors of test.php in cncs-sm2-php and of OpenSSL
+const VECTORS: &str = include_str!("../../fixtures/vectors.txt");
+
+fn vector(name: &str) -> &'static str {
+    VECTORS
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .unwrap()
+}
+
+fn pem(name: &str) -> String {
+    vector(name).replace("\\n", "\n")
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn test_verify() {
+    let public_key = PublicKey::from_hex(vector("PUBLIC_KEY")).unwrap();
+    let signature = Signature::from_hex(vector("SIGNATURE")).unwrap();
+    assert!(public_key
+        .verify(b"TEST", &signature, JsValue::UNDEFINED)
+        .unwrap());
+    assert!(!public_key
+        .verify(b"TEST2", &signature, JsValue::UNDEFINED)
+        .unwrap());
+
+    let signature = Signature::from_hex(vector("SIGNATURE_WITH_USER_ID")).unwrap();
+    assert!(public_key
+        .verify(b"TEST", &signature, "1234567812345678".into())
+        .unwrap());
+    assert!(!public_key
+        .verify(b"TEST", &signature, "ALICE123@YAHOO.COM".into())
+        .unwrap());
+
+    // Z_A hashes bytes, so the user id need not be UTF-8
+    let private_key = PrivateKey::from_hex_str(vector("PRIVATE_KEY")).unwrap();
+    let signature = sign_with_rng(&mut OsRng, &private_key, b"TEST", b"\xff\x00".as_slice())
+        .unwrap()
+        .to_concated_hex_str();
+    let signature = Signature::from_hex(&signature).unwrap();
+    assert!(public_key
+        .verify(
+            b"TEST",
+            &signature,
+            Uint8Array::from(&b"\xff\x00"[..]).into()
+        )
+        .unwrap());
+
+    let public_key = PublicKey::from_pem(&pem("OPENSSL_PUBLIC_KEY_PEM")).unwrap();
+    let signature = Signature::from_der(&from_hex(vector("OPENSSL_SIGNATURE_DER"))).unwrap();
+    assert_eq!(
+        signature.to_der(),
+        from_hex(vector("OPENSSL_SIGNATURE_DER"))
+    );
+    assert!(public_key.verify(b"TEST", &signature, "".into()).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_encrypt() {
+    let private_key = PrivateKey::from_hex_str(vector("PRIVATE_KEY")).unwrap();
+    let public_key = PublicKey::from_hex(vector("PUBLIC_KEY")).unwrap();
+
+    for (mode, kit_mode) in [
+        (None, KitEncryptMode::C1C3C2),
+        (Some(EncryptMode::C1C3C2), KitEncryptMode::C1C3C2),
+        (Some(EncryptMode::C1C2C3), KitEncryptMode::C1C2C3),
+    ] {
+        let ciphertext = public_key.encrypt("身份证号".as_bytes(), mode).unwrap();
+        assert_eq!(ciphertext.len(), 1 + 64 + 32 + "身份证号".len());
+        assert_eq!(
+            decrypt(&private_key, &ciphertext, kit_mode).unwrap(),
+            "身份证号".as_bytes()
+        );
+    }
+
+    // crypto.getRandomValues gives a fresh k each time
+    let ciphertext = public_key.encrypt(b"TEST", None).unwrap();
+    assert_ne!(public_key.encrypt(b"TEST", None).unwrap(), ciphertext);
+}
+
+#[wasm_bindgen_test]
+fn test_encrypt_to_base64() {
+    let public_key = PublicKey::from_hex(vector("PUBLIC_KEY")).unwrap();
+    let base64 = public_key
+        .encrypt_to_base64("TEST", Some(EncryptMode::C1C2C3))
+        .unwrap();
+    // 04 || x, base64 encoded
+    assert!(base64.starts_with('B'));
+    assert_eq!(base64.len(), (1 + 64 + 32 + 4_usize).div_ceil(3) * 4);
+}
+
+#[wasm_bindgen_test]
+fn test_key_formats() {
+    let public_key =
+        PublicKey::from_hex(&format!("04{}", vector("PUBLIC_KEY").to_lowercase())).unwrap();
+    assert_eq!(public_key.to_hex(), vector("PUBLIC_KEY"));
+    assert_eq!(
+        PublicKey::from_bytes(&public_key.to_bytes())
+            .unwrap()
+            .to_hex(),
+        vector("PUBLIC_KEY")
+    );
+    assert_eq!(
+        PublicKey::from_der(&public_key.to_der()).unwrap().to_hex(),
+        vector("PUBLIC_KEY")
+    );
+    assert_eq!(
+        PublicKey::from_pem(&public_key.to_pem()).unwrap().to_hex(),
+        vector("PUBLIC_KEY")
+    );
+
+    let public_key = PublicKey::from_pem(&pem("OPENSSL_PUBLIC_KEY_PEM")).unwrap();
+    assert_eq!(public_key.to_pem(), pem("OPENSSL_PUBLIC_KEY_PEM"));
+
+    let signature = Signature::from_hex(vector("SIGNATURE")).unwrap();
+    assert_eq!(signature.to_hex(), vector("SIGNATURE"));
+    assert_eq!(
+        Signature::from_bytes(&signature.to_bytes())
+            .unwrap()
+            .to_hex(),
+        vector("SIGNATURE")
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_errors() {
+    assert!(PublicKey::from_hex("XYZ").is_err());
+    assert!(PublicKey::from_hex("04").is_err());
+    // Off the curve
+    assert!(PublicKey::from_hex(&format!("{}B", &vector("PUBLIC_KEY")[..127])).is_err());
+    assert!(
+        PublicKey::from_pem(&pem("OPENSSL_PUBLIC_KEY_PEM").replace("PUBLIC", "PRIVATE")).is_err()
+    );
+    assert!(Signature::from_hex(&"00".repeat(64)).is_err());
+    assert!(Signature::from_der(&[0x30, 0x00]).is_err());
+
+    let public_key = PublicKey::from_hex(vector("PUBLIC_KEY")).unwrap();
+    let signature = Signature::from_hex(vector("SIGNATURE")).unwrap();
+    assert!(public_key
+        .verify(b"TEST", &signature, "A".repeat(8192).into())
+        .is_err());
+}